@@ -0,0 +1,71 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_bench_anemoi::bn254;
+use halo2_bench_anemoi::merkle::AnemoiMerkleBenchmark;
+use halo2_bench_anemoi::pasta::AnemoiBenchmark;
+use halo2_bench_backend::Ipa;
+use halo2_bench_harness::cargo_bench::prove_and_verify;
+use halo2_bench_harness::merkle::DEPTHS;
+use halo2_bench_harness::{max_hashes, min_k};
+
+const BATCH_K: u32 = 12;
+
+/// Message lengths for the sponge, each at its smallest `k`.
+const SPONGE_LENGTHS: [usize; 3] = [64, 256, 1024];
+
+/// This crate's BN254 scalar-field circuit, proven with the Delphinus fork's
+/// KZG: a single hash, and as many hashes as fit in `2^BATCH_K` rows.
+fn anemoi_bn254(c: &mut Criterion) {
+    let bench = bn254::AnemoiBenchmark::new();
+    let k = min_k(&bench).expect("the BN254 instance counts its used rows");
+    prove_and_verify(c, &bench, &[k, k + 1]);
+
+    let n =
+        max_hashes(BATCH_K, bn254::AnemoiBenchmark::batch).expect("a hash fits in 2^BATCH_K rows");
+    prove_and_verify(c, &bn254::AnemoiBenchmark::batch(n), &[BATCH_K]);
+}
+
+/// The Pasta instance, proven with IPA on Vesta like Poseidon and MiMC: a
+/// single hash, and as many hashes as fit in `2^BATCH_K` rows.
+fn anemoi_pasta(c: &mut Criterion) {
+    prove_and_verify(c, &AnemoiBenchmark::new(Ipa), &[7, 8, 9]);
+
+    let n = max_hashes(BATCH_K, |n| AnemoiBenchmark::batch(Ipa, n))
+        .expect("a hash fits in 2^BATCH_K rows");
+    prove_and_verify(c, &AnemoiBenchmark::batch(Ipa, n), &[BATCH_K]);
+}
+
+fn anemoi_sponge(c: &mut Criterion) {
+    for len in SPONGE_LENGTHS {
+        let bench = AnemoiBenchmark::sponge(Ipa, len);
+        let k = min_k(&bench).expect("the sponge reports its used rows");
+        prove_and_verify(c, &bench, &[k]);
+    }
+}
+
+/// A single compression, and as many as fit in `2^BATCH_K` rows.
+fn anemoi_jive(c: &mut Criterion) {
+    prove_and_verify(c, &AnemoiBenchmark::jive(Ipa, 1), &[7, 8, 9]);
+
+    let n = max_hashes(BATCH_K, |n| AnemoiBenchmark::jive(Ipa, n))
+        .expect("a compression fits in 2^BATCH_K rows");
+    prove_and_verify(c, &AnemoiBenchmark::jive(Ipa, n), &[BATCH_K]);
+}
+
+/// Merkle paths of each depth in [`DEPTHS`], each at its smallest `k`.
+fn anemoi_merkle(c: &mut Criterion) {
+    for depth in DEPTHS {
+        let bench = AnemoiMerkleBenchmark::new(Ipa, depth);
+        let k = min_k(&bench).expect("the Merkle path reports its used rows");
+        prove_and_verify(c, &bench, &[k]);
+    }
+}
+
+criterion_group!(
+    benches,
+    anemoi_bn254,
+    anemoi_pasta,
+    anemoi_sponge,
+    anemoi_jive,
+    anemoi_merkle
+);
+criterion_main!(benches);
@@ -1,7 +1,3 @@
 #!/bin/bash
 
-TIME=gtime
-which $TIME
-
-
-RUSTFLAGS=-Ctarget-cpu=native $TIME -f "Peak memory: %M kb CPU usage: %P" cargo run --release --package halo2_bench_anemoi --bin halo2_bench_anemoi
+RUSTFLAGS=-Ctarget-cpu=native cargo run --release --package halo2_bench_anemoi --bin halo2_bench_anemoi
@@ -0,0 +1,456 @@
+//! Anemoi over the BN254 scalar field, proven with the Delphinus fork's KZG.
+//!
+//! KZG on BN254 commits to polynomials over the scalar field `Fr`, not over the
+//! base field `Fq` the instance of the crate root and `AnemoiChip` live in.
+//! `AnemoiChip::<Fr>` cannot stand in: it takes `C`, `D` and `DELTA`, but
+//! fixes the multiplier `BETA` and computes the S-box root with the inverse
+//! exponent of `Fq`, which does not invert `x^ALPHA` in `Fr`. This module
+//! therefore instantiates the same single-column permutation over `Fr`, with
+//! round constants derived by [`constants`](crate::constants) for its
+//! generator, and lays it out with the gates `sponge_gates!` shares with
+//! [`pasta`](crate::pasta), so the benchmark goes through params, keygen,
+//! proving and verification like Poseidon and MiMC do. It measures this
+//! crate's circuit, not the Delphinus chip.
+//!
+//! As in [`BatchCircuit`](crate::BatchCircuit), the hashes of a batch are laid
+//! out one below the other in a single region, at offsets of
+//! [`ROWS_PER_HASH`].
+
+use crate::native::{linear_layer, Anemoi};
+use crate::{constraint_stats, ALPHA};
+use halo2_bench_harness::{CircuitStats, HashBenchmark, KeySizes};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{floor_planner::FlatFloorPlanner, AssignedCell, Layouter, Region},
+    pairing::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
+        ConstraintSystem, Error, Expression, Fixed, Instance, ProvingKey, SingleVerifier,
+        VirtualCells,
+    },
+    poly::{
+        commitment::{Params, ParamsVerifier},
+        Rotation,
+    },
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand::rngs::OsRng;
+use std::io;
+use std::sync::OnceLock;
+use zkwasm_host_circuits::{
+    circuits::anemoi::{NUM_HASH_ROUNDS, RATE},
+    value_for_assign,
+};
+
+/// Smallest generator of the multiplicative group of `Fr`; 3, the generator
+/// of the base-field instance, is a quadratic residue in `Fr`.
+const GENERATOR: u64 = 5;
+
+/// `1 / ALPHA` modulo `r - 1` for the BN254 scalar field.
+const INV_ALPHA: [u64; 4] = [
+    0xcfe7f7a98ccccccd,
+    0x535cb9d394945a0d,
+    0x93736af8679aad17,
+    0x26b6a528b427b354,
+];
+
+/// Rows one hash takes: the zero state, then an absorbed row, the rounds and
+/// the final linear layer for each message element.
+pub const ROWS_PER_HASH: usize = 1 + RATE * (NUM_HASH_ROUNDS + 2);
+
+/// The single-column instance over the BN254 scalar field, derived on first
+/// use.
+pub fn instance() -> &'static Anemoi<Fr> {
+    static INSTANCE: OnceLock<Anemoi<Fr>> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        let g = Fr::from(GENERATOR);
+        Anemoi::new(
+            NUM_HASH_ROUNDS,
+            ALPHA as u64,
+            g,
+            g.invert().unwrap(),
+            INV_ALPHA,
+        )
+    })
+}
+
+/// Columns and gates of the sponge.
+///
+/// The selectors are fixed columns, as in the `CommonGateConfig` the
+/// Delphinus chips share.
+#[derive(Debug, Clone)]
+pub struct AnemoiConfig {
+    state: [Column<Advice>; 2],
+    message: Column<Advice>,
+    c: Column<Fixed>,
+    d: Column<Fixed>,
+    expected: Column<Instance>,
+    /// The state on the row is zero.
+    start: Column<Fixed>,
+    /// `x` on the next row is `x` plus the message element.
+    absorb: Column<Fixed>,
+    /// The next row is one round away.
+    round: Column<Fixed>,
+    /// The next row is one linear layer away.
+    linear: Column<Fixed>,
+}
+
+impl AnemoiConfig {
+    pub fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+        let state = [meta.advice_column(), meta.advice_column()];
+        let message = meta.advice_column();
+        let expected = meta.instance_column();
+        meta.enable_equality(state[0]);
+        meta.enable_equality(expected);
+
+        let config = Self {
+            state,
+            message,
+            c: meta.fixed_column(),
+            d: meta.fixed_column(),
+            expected,
+            start: meta.fixed_column(),
+            absorb: meta.fixed_column(),
+            round: meta.fixed_column(),
+            linear: meta.fixed_column(),
+        };
+        sponge_gates!(
+            meta,
+            config,
+            instance(),
+            |meta: &mut VirtualCells<'_, Fr>, selector| meta.query_fixed(selector, Rotation::cur()),
+            |meta: &mut VirtualCells<'_, Fr>, column| meta.query_fixed(column, Rotation::cur()),
+        );
+
+        config
+    }
+
+    /// Lays out the sponge over `message` from `offset` on, and returns the
+    /// cell holding the digest.
+    pub fn hash(
+        &self,
+        region: &Region<Fr>,
+        offset: usize,
+        message: &[Fr; RATE],
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        let permutation_rows = instance().rounds() + 2;
+        self.enable(region, self.start, offset)?;
+        let mut x = None;
+        for (row, state) in sponge_trace(message).into_iter().enumerate() {
+            x = Some(self.assign_state(region, offset + row, state)?);
+        }
+
+        for (i, &element) in message.iter().enumerate() {
+            let offset = offset + i * permutation_rows;
+            self.enable(region, self.absorb, offset)?;
+            region.assign_advice(
+                || format!("message_{}", i),
+                self.message,
+                offset,
+                || value_for_assign!(element),
+            )?;
+            self.enable_permutation(region, offset + 1)?;
+        }
+
+        Ok(x.expect("the sponge has at least the starting row"))
+    }
+
+    /// Assigns `state` to `row`, and returns the cell holding `x`.
+    fn assign_state(
+        &self,
+        region: &Region<Fr>,
+        row: usize,
+        state: [Fr; 2],
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        let x = region.assign_advice(
+            || format!("x_{}", row),
+            self.state[0],
+            row,
+            || value_for_assign!(state[0]),
+        )?;
+        region.assign_advice(
+            || format!("y_{}", row),
+            self.state[1],
+            row,
+            || value_for_assign!(state[1]),
+        )?;
+        Ok(x)
+    }
+
+    /// Constrains the state from `offset` on to go through the permutation,
+    /// with the result `rounds + 1` rows below.
+    fn enable_permutation(&self, region: &Region<Fr>, offset: usize) -> Result<(), Error> {
+        let anemoi = instance();
+        for (round, (&c, &d)) in anemoi.c.iter().zip(&anemoi.d).enumerate() {
+            let offset = offset + round;
+            self.enable(region, self.round, offset)?;
+            region.assign_fixed(
+                || format!("c_{}", round),
+                self.c,
+                offset,
+                || value_for_assign!(c),
+            )?;
+            region.assign_fixed(
+                || format!("d_{}", round),
+                self.d,
+                offset,
+                || value_for_assign!(d),
+            )?;
+        }
+        self.enable(region, self.linear, offset + anemoi.rounds())
+    }
+
+    /// Sets the fixed `selector` on `offset`.
+    fn enable(
+        &self,
+        region: &Region<Fr>,
+        selector: Column<Fixed>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "selector",
+            selector,
+            offset,
+            || value_for_assign!(Fr::one()),
+        )?;
+        Ok(())
+    }
+}
+
+/// The state on every row [`AnemoiConfig::hash`] lays out for `message`.
+fn sponge_trace(message: &[Fr]) -> Vec<[Fr; 2]> {
+    let anemoi = instance();
+    let mut state = [Fr::zero(); 2];
+    let mut rows = vec![state];
+    for &element in message {
+        state[0] += element;
+        rows.push(state);
+        for round in 0..anemoi.rounds() {
+            anemoi.round(round, &mut state);
+            rows.push(state);
+        }
+        linear_layer(&mut state);
+        rows.push(state);
+    }
+    rows
+}
+
+/// Proves that row `i` of the instance column is the digest of the `i`-th
+/// message.
+#[derive(Debug, Clone, Default)]
+pub struct AnemoiCircuit {
+    pub messages: Vec<[Fr; RATE]>,
+}
+
+impl Circuit<Fr> for AnemoiCircuit {
+    type Config = AnemoiConfig;
+    type FloorPlanner = FlatFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            messages: vec![[Fr::zero(); RATE]; self.messages.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        AnemoiConfig::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let digests = layouter.assign_region(
+            || "anemoi batch",
+            |region| {
+                self.messages
+                    .iter()
+                    .enumerate()
+                    .map(|(i, message)| config.hash(&region, i * ROWS_PER_HASH, message))
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        for (i, digest) in digests.iter().enumerate() {
+            layouter.constrain_instance(digest.cell(), config.expected, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Anemoi over the BN254 scalar field, proven with KZG on BN254.
+///
+/// Hashes messages of `RATE` elements, one at a time unless built with
+/// [`batch`](Self::batch).
+#[derive(Debug, Clone, Copy)]
+pub struct AnemoiBenchmark {
+    hashes: usize,
+}
+
+impl AnemoiBenchmark {
+    /// Proves a single hash.
+    pub fn new() -> Self {
+        Self::batch(1)
+    }
+
+    /// Proves `hashes` independent hashes in one circuit.
+    pub fn batch(hashes: usize) -> Self {
+        Self { hashes }
+    }
+
+    fn empty_circuit(&self) -> AnemoiCircuit {
+        AnemoiCircuit {
+            messages: vec![[Fr::zero(); RATE]; self.hashes],
+        }
+    }
+}
+
+impl Default for AnemoiBenchmark {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashBenchmark for AnemoiBenchmark {
+    type Params = Params<G1Affine>;
+    type ProvingKey = ProvingKey<G1Affine>;
+    type Message = Vec<[Fr; RATE]>;
+    type Digest = Vec<Fr>;
+    type Circuit = AnemoiCircuit;
+
+    fn name(&self) -> String {
+        match self.hashes {
+            1 => "anemoi-bn254".to_string(),
+            n => format!("anemoi-bn254-n{}", n),
+        }
+    }
+
+    fn curve(&self) -> &'static str {
+        "bn254"
+    }
+
+    fn field(&self) -> &'static str {
+        "bn254-scalar"
+    }
+
+    fn backend(&self) -> &'static str {
+        "kzg-delphinus"
+    }
+
+    /// The layout is fixed by [`ROWS_PER_HASH`], so the used rows are counted
+    /// rather than measured.
+    fn circuit_stats(&self, _k: u32) -> CircuitStats {
+        CircuitStats {
+            used_rows: Some(self.hashes * ROWS_PER_HASH),
+            ..constraint_stats::<Fr, AnemoiCircuit>()
+        }
+    }
+
+    fn message_len(&self) -> usize {
+        RATE
+    }
+
+    fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    fn setup(&self, k: u32) -> Self::Params {
+        Params::<G1Affine>::unsafe_setup::<Bn256>(k)
+    }
+
+    fn keygen(&self, params: &Self::Params) -> Self::ProvingKey {
+        let empty_circuit = self.empty_circuit();
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    fn random_message(&self) -> Self::Message {
+        (0..self.hashes)
+            .map(|_| [(); RATE].map(|_| Fr::random(OsRng)))
+            .collect()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message
+            .iter()
+            .map(|message| instance().hash(message))
+            .collect()
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        AnemoiCircuit { messages: message }
+    }
+
+    fn prove(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: Self::Circuit,
+        digest: &Self::Digest,
+    ) -> Vec<u8> {
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof(
+            params,
+            pk,
+            &[circuit],
+            &[&[digest.as_slice()]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        proof: &[u8],
+        digest: &Self::Digest,
+    ) -> bool {
+        let params: ParamsVerifier<Bn256> = match params.verifier(digest.len()) {
+            Ok(params) => params,
+            Err(_) => return false,
+        };
+        let strategy = SingleVerifier::new(&params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof(
+            &params,
+            pk.get_vk(),
+            strategy,
+            &[&[digest.as_slice()]],
+            &mut transcript,
+        )
+        .is_ok()
+    }
+
+    /// The Delphinus fork serializes the params and the verifying key, but
+    /// not the proving key.
+    fn key_sizes(&self, params: &Self::Params, pk: &Self::ProvingKey) -> KeySizes {
+        let mut vk = vec![];
+        pk.get_vk()
+            .write(&mut vk)
+            .expect("writing to a Vec should not fail");
+        KeySizes {
+            params: Some(
+                self.params_to_bytes(params)
+                    .expect("writing to a Vec should not fail")
+                    .len(),
+            ),
+            vk: Some(vk.len()),
+            ..KeySizes::default()
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        AnemoiCircuit::configure(&mut meta);
+        format!("{} {:?} {:?}", self.name(), meta, instance())
+    }
+
+    fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![];
+        params.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn params_from_bytes(&self, bytes: &[u8]) -> io::Result<Self::Params> {
+        Params::read(bytes)
+    }
+}
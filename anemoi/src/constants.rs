@@ -0,0 +1,98 @@
+//! Anemoi round constants, derived as in the specification.
+//!
+//! With `g` the generator the S-box multiplies by, and `π_0`, `π_1` the first
+//! two 100-digit blocks of the decimals of π, round `r` and column `i` add
+//!
+//! ```text
+//! C[r][i] = g (π_0^r)^2 + (π_0^r + π_1^i)^ALPHA
+//! D[r][i] = g (π_1^i)^2 + (π_0^r + π_1^i)^ALPHA + g^-1
+//! ```
+//!
+//! This only takes ring arithmetic, so it is written against
+//! [`AnemoiField`] rather than an `ff` trait. The halo2 forks in this
+//! workspace pin different `ff` versions, and the same derivation serves the
+//! fields of all of them.
+
+use std::ops::{Add, Mul, Sub};
+
+/// Arithmetic the derivation and the native permutation need, implemented by
+/// the field types of every `ff` version.
+pub trait AnemoiField:
+    Copy + From<u64> + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+}
+
+impl<F> AnemoiField for F where
+    F: Copy + From<u64> + Add<Output = F> + Sub<Output = F> + Mul<Output = F>
+{
+}
+
+/// The first 100 decimals of π.
+const PI_0: &str = "1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+
+/// The next 100 decimals of π.
+const PI_1: &str = "8214808651328230664709384460955058223172535940812848111745028410270193852110555964462294895493038196";
+
+/// Additive round constants, indexed by round, then column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundConstants<F> {
+    pub c: Vec<Vec<F>>,
+    pub d: Vec<Vec<F>>,
+}
+
+/// Round constants for `rounds` rounds of a `columns`-column instance with
+/// S-box exponent `alpha`, generator `g` and its inverse `g_inv`.
+pub fn round_constants<F: AnemoiField>(
+    rounds: usize,
+    columns: usize,
+    alpha: u64,
+    g: F,
+    g_inv: F,
+) -> RoundConstants<F> {
+    let pi_0 = decimal::<F>(PI_0);
+    let pi_1 = decimal::<F>(PI_1);
+    let pi_1_powers: Vec<F> = powers(pi_1).take(columns).collect();
+
+    let (c, d) = powers(pi_0)
+        .take(rounds)
+        .map(|pi_0_r| {
+            pi_1_powers
+                .iter()
+                .map(|&pi_1_i| {
+                    let sum = pow(pi_0_r + pi_1_i, &[alpha]);
+                    let c = g * pi_0_r * pi_0_r + sum;
+                    let d = g * pi_1_i * pi_1_i + sum + g_inv;
+                    (c, d)
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>()
+        })
+        .unzip();
+    RoundConstants { c, d }
+}
+
+/// `digits`, read as a decimal number and reduced into `F`.
+fn decimal<F: AnemoiField>(digits: &str) -> F {
+    digits.bytes().fold(F::from(0), |acc, digit| {
+        assert!(digit.is_ascii_digit(), "not a decimal digit: {}", digit);
+        acc * F::from(10) + F::from((digit - b'0') as u64)
+    })
+}
+
+/// `1, x, x^2, ...`
+fn powers<F: AnemoiField>(x: F) -> impl Iterator<Item = F> {
+    std::iter::successors(Some(F::from(1)), move |&power| Some(power * x))
+}
+
+/// `x^exp`, with `exp` given as little-endian limbs.
+pub(crate) fn pow<F: AnemoiField>(x: F, exp: &[u64]) -> F {
+    exp.iter().rev().fold(F::from(1), |acc, &limb| {
+        (0..u64::BITS).rev().fold(acc, |acc, bit| {
+            let acc = acc * acc;
+            if (limb >> bit) & 1 == 1 {
+                acc * x
+            } else {
+                acc
+            }
+        })
+    })
+}
@@ -0,0 +1,419 @@
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{floor_planner::FlatFloorPlanner, Chip, Layouter, Region},
+    dev::MockProver,
+    pairing::bn256::Fq as Felt,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+};
+
+use halo2_bench_harness::{debug_count, CircuitStats};
+use rand::rngs::OsRng;
+use std::convert::TryInto;
+use std::sync::OnceLock;
+
+use zkwasm_host_circuits::{
+    circuits::{
+        anemoi::{AnemoiChip, NUM_HASH_ROUNDS, RATE},
+        CommonGateConfig,
+    },
+    utils::Limb,
+    value_for_assign,
+};
+
+/// Creates the `start`, `absorb`, `round` and `linear` gates of the
+/// single-column sponge laid out by `$config`, on whichever `halo2_proofs`
+/// fork is in scope where it is expanded.
+///
+/// [`pasta`] and [`bn254`] prove the same layout on different forks, whose
+/// gate APIs only differ in how a selector and a fixed column are queried at
+/// the current row; `$selector` and `$fixed` are closures doing that.
+///
+/// Every round takes one row: with `(x, y)` on the row and `(x', y')` on the
+/// next, the S-box output is checked through `t = y_1 - y'`, the `ALPHA`-th
+/// root the S-box subtracts from `y_1`, which keeps the gate at `t^ALPHA`
+/// instead of an inverse power.
+macro_rules! sponge_gates {
+    ($meta:expr, $config:expr, $anemoi:expr, $selector:expr, $fixed:expr $(,)?) => {{
+        let config = &$config;
+        let anemoi = $anemoi;
+        let selector = $selector;
+        let fixed = $fixed;
+        let [x, y] = config.state;
+
+        $meta.create_gate("start", |meta| {
+            let s = selector(meta, config.start);
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            vec![s.clone() * x, s * y]
+        });
+
+        $meta.create_gate("absorb", |meta| {
+            let s = selector(meta, config.absorb);
+            let m = meta.query_advice(config.message, Rotation::cur());
+            let x_next = meta.query_advice(x, Rotation::next());
+            let y_next = meta.query_advice(y, Rotation::next());
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            vec![s.clone() * (x_next - x - m), s * (y_next - y)]
+        });
+
+        $meta.create_gate("round", |meta| {
+            let s = selector(meta, config.round);
+            let x_next = meta.query_advice(x, Rotation::next());
+            let y_next = meta.query_advice(y, Rotation::next());
+            let x = meta.query_advice(x, Rotation::cur()) + fixed(meta, config.c);
+            let y = meta.query_advice(y, Rotation::cur()) + fixed(meta, config.d);
+            let beta = Expression::Constant(anemoi.beta);
+            let delta = Expression::Constant(anemoi.delta);
+
+            // Linear layer.
+            let y = y + x.clone();
+            let x = x + y.clone();
+            // Open Flystel, with `t` the root it subtracts from `y`.
+            let x = x - beta.clone() * y.clone() * y.clone();
+            let t = y - y_next.clone();
+            let t2 = t.clone() * t.clone();
+            vec![
+                s.clone() * (t2.clone() * t2 * t - x.clone()),
+                s * (x_next - x - beta * y_next.clone() * y_next - delta),
+            ]
+        });
+
+        $meta.create_gate("linear", |meta| {
+            let s = selector(meta, config.linear);
+            let x_next = meta.query_advice(x, Rotation::next());
+            let y_next = meta.query_advice(y, Rotation::next());
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            let y = y + x.clone();
+            vec![s.clone() * (y_next - y.clone()), s * (x_next - x - y)]
+        });
+    }};
+}
+
+pub mod bn254;
+pub mod constants;
+pub mod merkle;
+pub mod native;
+pub mod pasta;
+
+use native::Anemoi;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Exponent of the Anemoi S-Box
+const ALPHA: u32 = 5;
+
+/// Inverse exponent
+const INV_ALPHA: [u64; 4] = [
+    0x180d04d5f031fee9,
+    0xd633c43a29c71dd2,
+    0x49b9b57c33cd568b,
+    0x135b52945a13d9aa,
+];
+
+/// Multiplier of the Anemoi S-Box
+const BETA: u32 = 3;
+
+/// First added constant of the Anemoi S-Box
+const DELTA: Felt = Felt::from_raw([
+    0xd2c05d6490535385,
+    0xba56470b9af68708,
+    0xd03583cf0100e593,
+    0x2042def740cbc01b,
+]);
+
+/// The single-column instance `AnemoiChip` implements, derived on first use.
+pub fn instance() -> &'static Anemoi<Felt> {
+    static INSTANCE: OnceLock<Anemoi<Felt>> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        Anemoi::new(
+            NUM_HASH_ROUNDS,
+            ALPHA as u64,
+            Felt::from(BETA as u64),
+            DELTA,
+            INV_ALPHA,
+        )
+    })
+}
+
+/// Additive round constants `C` and `D` of [`instance`], as `AnemoiChip`
+/// takes them.
+pub fn round_constants() -> ([Felt; NUM_HASH_ROUNDS], [Felt; NUM_HASH_ROUNDS]) {
+    let instance = instance();
+    (
+        instance.c.clone().try_into().unwrap(),
+        instance.d.clone().try_into().unwrap(),
+    )
+}
+
+// HelperChip and its implementations
+#[derive(Clone, Debug)]
+pub struct HelperChipConfig {
+    limb: Column<Advice>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HelperChip {
+    config: HelperChipConfig,
+}
+
+impl Chip<Felt> for HelperChip {
+    type Config = HelperChipConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl HelperChip {
+    fn new(config: HelperChipConfig) -> Self {
+        HelperChip { config }
+    }
+
+    fn configure(cs: &mut ConstraintSystem<Felt>) -> HelperChipConfig {
+        let limb = cs.advice_column();
+        cs.enable_equality(limb);
+        HelperChipConfig { limb }
+    }
+
+    fn assign_inputs(
+        &self,
+        region: &Region<Felt>,
+        offset: &mut usize,
+        inputs: &[Felt; RATE],
+    ) -> Result<[Limb<Felt>; RATE], Error> {
+        let mut limbs = Vec::with_capacity(RATE);
+        for x in inputs {
+            let c = region.assign_advice(
+                || "assign input",
+                self.config.limb,
+                *offset,
+                || value_for_assign!(*x),
+            )?;
+            *offset += 1;
+            limbs.push(Limb::new(Some(c), *x));
+        }
+        Ok(limbs.try_into().ok().expect("one limb per input"))
+    }
+
+    fn assign_result(
+        &self,
+        region: &Region<Felt>,
+        offset: &mut usize,
+        result: &Felt,
+    ) -> Result<Limb<Felt>, Error> {
+        let c = region.assign_advice(
+            || format!("assign result"),
+            self.config.limb,
+            *offset,
+            || value_for_assign!(result.clone()),
+        )?;
+        *offset += 1;
+        Ok(Limb::new(Some(c), result.clone()))
+    }
+}
+
+// TestCircuit and its implementations
+#[derive(Clone, Debug, Default)]
+pub struct TestCircuit {
+    pub inputs: Vec<Felt>,
+    pub result: Felt,
+}
+
+#[derive(Clone, Debug)]
+pub struct TestConfig {
+    anemoiconfig: CommonGateConfig,
+    helperconfig: HelperChipConfig,
+}
+
+impl Circuit<Felt> for TestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = FlatFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        test_vector([Felt::zero(); RATE])
+    }
+
+    fn configure(cs: &mut ConstraintSystem<Felt>) -> Self::Config {
+        let witness = vec![
+            cs.advice_column(),
+            cs.advice_column(),
+            cs.advice_column(),
+            cs.advice_column(),
+            cs.advice_column(),
+        ];
+        Self::Config {
+            anemoiconfig: AnemoiChip::<Felt>::configure(cs, &witness),
+            helperconfig: HelperChip::configure(cs),
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Felt>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign anemoi test",
+            |region| {
+                let helperchip = HelperChip::new(config.clone().helperconfig);
+                let (c, d) = round_constants();
+                let mut anemoichip =
+                    AnemoiChip::<Felt>::construct(config.clone().anemoiconfig, c, d, DELTA);
+                let mut offset = 0;
+                let result = helperchip.assign_result(&region, &mut offset, &self.result)?;
+                let input = helperchip.assign_inputs(
+                    &region,
+                    &mut offset,
+                    &self.inputs.clone().try_into().unwrap(),
+                )?;
+                offset = 0;
+                anemoichip.initialize(&config.anemoiconfig, &region, &mut offset)?; // init to all zeros
+                anemoichip.hash(&region, &mut offset, &input, &result)?;
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// Several [`TestCircuit`]s hashed one after the other in a single region.
+#[derive(Clone, Debug, Default)]
+pub struct BatchCircuit(pub Vec<TestCircuit>);
+
+impl Circuit<Felt> for BatchCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = FlatFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(self.0.iter().map(TestCircuit::without_witnesses).collect())
+    }
+
+    fn configure(cs: &mut ConstraintSystem<Felt>) -> Self::Config {
+        TestCircuit::configure(cs)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Felt>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign anemoi batch",
+            |region| {
+                let helperchip = HelperChip::new(config.clone().helperconfig);
+                let (c, d) = round_constants();
+                let mut anemoichip =
+                    AnemoiChip::<Felt>::construct(config.clone().anemoiconfig, c, d, DELTA);
+                // The helper column and the Anemoi columns fill up independently.
+                let mut helper_offset = 0;
+                let mut offset = 0;
+                for circuit in &self.0 {
+                    let result =
+                        helperchip.assign_result(&region, &mut helper_offset, &circuit.result)?;
+                    let input = helperchip.assign_inputs(
+                        &region,
+                        &mut helper_offset,
+                        &circuit.inputs.clone().try_into().unwrap(),
+                    )?;
+                    anemoichip.initialize(&config.anemoiconfig, &region, &mut offset)?;
+                    anemoichip.hash(&region, &mut offset, &input, &result)?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+/// A [`TestCircuit`] for `inputs`, with the result computed by
+/// [`native::hash`].
+pub fn test_vector(inputs: [Felt; RATE]) -> TestCircuit {
+    TestCircuit {
+        inputs: inputs.to_vec(),
+        result: native::hash(&inputs),
+    }
+}
+
+/// A [`TestCircuit`] for a random message.
+pub fn random_circuit() -> TestCircuit {
+    test_vector([(); RATE].map(|_| Felt::random(OsRng)))
+}
+
+/// Circuits for the known Anemoi test vectors, which [`native::hash`] must
+/// reproduce.
+pub fn test_circuits() -> Vec<TestCircuit> {
+    let input_data = [
+        vec![Felt::one(), Felt::one()],
+        vec![Felt::zero(), Felt::zero()],
+    ];
+
+    let expected = [
+        Felt::from_raw([
+            0x9f72277137a37266,
+            0x17bdddc79f44f08b,
+            0x76008edf3b0d7d10,
+            0x11f013adb9e0ff65,
+        ]),
+        Felt::from_raw([
+            0x94672c47f345700a,
+            0xe5168077fd5eeb90,
+            0xae14f132fcc041ec,
+            0x2ac427786f4818bf,
+        ]),
+    ];
+
+    input_data
+        .iter()
+        .zip(expected)
+        .map(|(inputs, result)| TestCircuit {
+            inputs: inputs.clone(),
+            result,
+        })
+        .collect()
+}
+
+/// Columns and gates of [`TestCircuit`].
+///
+/// Used rows are not measured on the Delphinus fork of `halo2_proofs`.
+pub fn circuit_stats() -> CircuitStats {
+    constraint_stats::<Felt, TestCircuit>()
+}
+
+/// Columns, gates and degree of `C` on the Delphinus fork, without used rows.
+///
+/// The fork keeps the counts crate-private, so they are read out of the
+/// `Debug` dump; `tests/stats.rs` pins them on a circuit of known shape.
+pub fn constraint_stats<F: FieldExt, C: Circuit<F>>() -> CircuitStats {
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+    let degree = meta.degree();
+    let blinding_factors = meta.blinding_factors();
+    let meta = format!("{:?}", meta);
+    CircuitStats {
+        advice_columns: debug_count(&meta, "num_advice_columns"),
+        fixed_columns: debug_count(&meta, "num_fixed_columns"),
+        instance_columns: debug_count(&meta, "num_instance_columns"),
+        gates: meta.matches("Gate {").count(),
+        lookups: meta.matches("input_expressions").count(),
+        max_degree: degree,
+        used_rows: None,
+        blinding_factors,
+    }
+}
+
+/// A batch of `hashes` random messages.
+pub fn random_batch(hashes: usize) -> BatchCircuit {
+    BatchCircuit((0..hashes).map(|_| random_circuit()).collect())
+}
+
+/// Smallest `k` on which `MockProver` accepts a batch of `hashes` messages.
+///
+/// Without used rows to go by, this probes upwards from the rows the helper
+/// column needs for the digests and inputs, a bound the layout cannot go
+/// below; `MockProver::run` fails when the layout does not fit.
+pub fn min_k(hashes: usize) -> u32 {
+    let circuit = random_batch(hashes);
+    (circuit_stats().k_for(hashes * (RATE + 1))..)
+        .find(|&k| MockProver::run(k, &circuit, vec![]).is_ok())
+        .expect("some k fits the circuit")
+}
@@ -0,0 +1,235 @@
+//! Merkle path verification with Anemoi Jive as the two-to-one hash.
+//!
+//! Each level orders the current node and its sibling by the matching bit of
+//! the leaf index with a conditional swap gate, then compresses the pair with
+//! [`AnemoiConfig::jive_cells`] over `pallas::Base`. The root is the only
+//! public input; the leaf, siblings and index stay private.
+
+use crate::pasta::{instance, AnemoiConfig};
+use ff::Field;
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::merkle::{MerklePath, MerkleTree};
+use halo2_bench_harness::{BenchResult, CircuitStats, HashBenchmark, RunConfig};
+use halo2curves::pasta::Fp;
+use pse_halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error, Expression, ProvingKey, Selector},
+    poly::Rotation,
+};
+use rand::{rngs::OsRng, RngCore};
+
+/// Jive of `left` and `right`, the compression the tree is built with.
+pub fn compress(left: &Fp, right: &Fp) -> Fp {
+    instance().jive(*left, *right)
+}
+
+/// Circuit proving that the instance column holds the root of `path`.
+#[derive(Debug, Clone)]
+pub struct AnemoiMerkleCircuit {
+    path: Value<MerklePath<Fp>>,
+    depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnemoiMerkleConfig {
+    /// Node, sibling and index bit of a level sit in the two state columns
+    /// and the message column, with the ordered pair on the next row.
+    anemoi: AnemoiConfig,
+    swap: Selector,
+}
+
+impl Circuit<Fp> for AnemoiMerkleCircuit {
+    type Config = AnemoiMerkleConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            path: Value::unknown(),
+            depth: self.depth,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let anemoi = AnemoiConfig::configure(meta);
+        let [node, sibling] = anemoi.state;
+        let bit = anemoi.message;
+
+        let swap = meta.selector();
+        meta.create_gate("swap", |meta| {
+            let swap = meta.query_selector(swap);
+            let left = meta.query_advice(node, Rotation::next());
+            let right = meta.query_advice(sibling, Rotation::next());
+            let node = meta.query_advice(node, Rotation::cur());
+            let sibling = meta.query_advice(sibling, Rotation::cur());
+            let bit = meta.query_advice(bit, Rotation::cur());
+
+            let one = Expression::Constant(Fp::ONE);
+            vec![
+                swap.clone() * bit.clone() * (one - bit.clone()),
+                swap.clone()
+                    * (left.clone() - node.clone() - bit * (sibling.clone() - node.clone())),
+                swap * (left + right - node - sibling),
+            ]
+        });
+
+        AnemoiMerkleConfig { anemoi, swap }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let mut node = layouter.assign_region(
+            || "load leaf",
+            |mut region| {
+                let leaf = self.path.as_ref().map(|path| path.leaf);
+                region.assign_advice(|| "leaf", config.anemoi.state[0], 0, || leaf)
+            },
+        )?;
+
+        for height in 0..self.depth {
+            let pair = layouter.assign_region(
+                || format!("swap {}", height),
+                |mut region| swap(&config, &mut region, &node, &self.path, height),
+            )?;
+            node = config
+                .anemoi
+                .jive_cells(layouter.namespace(|| format!("jive {}", height)), &pair)?;
+        }
+
+        layouter.constrain_instance(node.cell(), config.anemoi.expected, 0)
+    }
+}
+
+/// Assigns `node` and its sibling at `height`, followed by the two in the
+/// order they are compressed.
+fn swap(
+    config: &AnemoiMerkleConfig,
+    region: &mut Region<'_, Fp>,
+    node: &AssignedCell<Fp, Fp>,
+    path: &Value<MerklePath<Fp>>,
+    height: usize,
+) -> Result<[AssignedCell<Fp, Fp>; 2], Error> {
+    config.swap.enable(region, 0)?;
+    let [node_column, sibling_column] = config.anemoi.state;
+
+    let sibling = path.as_ref().map(|path| path.siblings[height]);
+    let is_right = path.as_ref().map(|path| path.is_right(height));
+    node.copy_advice(|| "node", region, node_column, 0)?;
+    region.assign_advice(|| "sibling", sibling_column, 0, || sibling)?;
+    region.assign_advice(
+        || "index bit",
+        config.anemoi.message,
+        0,
+        || is_right.map(|is_right| Fp::from(is_right as u64)),
+    )?;
+
+    let (left, right) = node
+        .value()
+        .copied()
+        .zip(sibling)
+        .zip(is_right)
+        .map(|((node, sibling), is_right)| {
+            if is_right {
+                (sibling, node)
+            } else {
+                (node, sibling)
+            }
+        })
+        .unzip();
+    let left = region.assign_advice(|| "left", node_column, 1, || left)?;
+    let right = region.assign_advice(|| "right", sibling_column, 1, || right)?;
+    Ok([left, right])
+}
+
+/// Membership of a leaf in an Anemoi Jive Merkle tree of the given depth,
+/// proven with `B`.
+///
+/// Every level is one compression, so [`hashes`](HashBenchmark::hashes) is
+/// the depth.
+#[derive(Debug, Clone, Copy)]
+pub struct AnemoiMerkleBenchmark<B> {
+    backend: B,
+    depth: usize,
+}
+
+impl<B: Backend<Field = Fp>> AnemoiMerkleBenchmark<B> {
+    pub fn new(backend: B, depth: usize) -> Self {
+        Self { backend, depth }
+    }
+
+    fn empty_circuit(&self) -> AnemoiMerkleCircuit {
+        AnemoiMerkleCircuit {
+            path: Value::unknown(),
+            depth: self.depth,
+        }
+    }
+}
+
+impl<B: Backend<Field = Fp>> HashBenchmark for AnemoiMerkleBenchmark<B> {
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = MerklePath<Fp>;
+    type Digest = Fp;
+    type Circuit = AnemoiMerkleCircuit;
+
+    fn name(&self) -> String {
+        format!("anemoi-merkle-d{}", self.depth)
+    }
+
+    fn message_len(&self) -> usize {
+        2
+    }
+
+    fn hashes(&self) -> usize {
+        self.depth
+    }
+
+    fn random_message(&self) -> Self::Message {
+        MerkleTree::random_path(
+            self.depth,
+            Fp::ZERO,
+            compress,
+            || Fp::random(OsRng),
+            || OsRng.next_u64(),
+        )
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message.root(compress)
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        AnemoiMerkleCircuit {
+            path: Value::known(message),
+            depth: self.depth,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        AnemoiMerkleCircuit::configure(&mut meta);
+        format!("{} {:?} {:?}", self.name(), meta, instance())
+    }
+
+    forward_to_backend!(backend, |digest| &[*digest]);
+}
+
+/// Runs [`AnemoiMerkleBenchmark`] for a tree of `depth`.
+pub fn run_depth<B: Backend<Field = Fp>>(
+    backend: B,
+    depth: usize,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    halo2_bench_harness::run(&AnemoiMerkleBenchmark::new(backend, depth), config)
+}
+
+/// Layout of the path circuit for a tree of `depth` on `2^k` rows.
+pub fn circuit_stats_depth<B: Backend<Field = Fp>>(
+    backend: B,
+    depth: usize,
+    k: u32,
+) -> CircuitStats {
+    AnemoiMerkleBenchmark::new(backend, depth).circuit_stats(k)
+}
@@ -0,0 +1,111 @@
+//! Native Anemoi.
+//!
+//! [`Anemoi`] is a single-column instance, with a state `(x, y)`, like
+//! `AnemoiBn254_2_1` in the reference `anemoi-rust`. Its [`hash`](Anemoi::hash)
+//! is the sponge `AnemoiChip::hash` constrains: every message element is added
+//! to `x` before a permutation, and the digest is `x`. With a rate of one
+//! element no padding is needed. [`jive`](Anemoi::jive) is the Jive two-to-one
+//! compression built on the same permutation.
+//!
+//! The free functions hash over the BN254 base field, as `AnemoiChip` does.
+
+use crate::constants::{self, AnemoiField, RoundConstants};
+use crate::{instance, Felt};
+
+/// A single-column Anemoi instance over `F`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anemoi<F> {
+    /// Added to `x` in each round.
+    pub c: Vec<F>,
+    /// Added to `y` in each round.
+    pub d: Vec<F>,
+    /// Multiplier of the S-box, the generator `g`.
+    pub beta: F,
+    /// Constant added by the S-box, `g^-1`.
+    pub delta: F,
+    /// `1 / ALPHA` modulo `p - 1`, as little-endian limbs.
+    pub inv_alpha: [u64; 4],
+}
+
+impl<F: AnemoiField> Anemoi<F> {
+    /// The instance with `rounds` rounds, S-box exponent `alpha` and
+    /// generator `g`, with round constants derived as in the specification.
+    pub fn new(rounds: usize, alpha: u64, g: F, g_inv: F, inv_alpha: [u64; 4]) -> Self {
+        let RoundConstants { c, d } = constants::round_constants(rounds, 1, alpha, g, g_inv);
+        let column = |constants: Vec<Vec<F>>| constants.into_iter().map(|round| round[0]).collect();
+        Self {
+            c: column(c),
+            d: column(d),
+            beta: g,
+            delta: g_inv,
+            inv_alpha,
+        }
+    }
+
+    /// Number of rounds of the permutation.
+    pub fn rounds(&self) -> usize {
+        self.c.len()
+    }
+
+    /// Hashes `message` with the sponge `AnemoiChip::hash` constrains.
+    pub fn hash(&self, message: &[F]) -> F {
+        let mut state = [F::from(0); 2];
+        for &element in message {
+            state[0] = state[0] + element;
+            self.permute(&mut state);
+        }
+        state[0]
+    }
+
+    /// Compresses two elements into one: the sum of the inputs and the
+    /// permuted state.
+    pub fn jive(&self, left: F, right: F) -> F {
+        let mut state = [left, right];
+        self.permute(&mut state);
+        left + right + state[0] + state[1]
+    }
+
+    /// The Anemoi permutation, followed by a final linear layer as in the
+    /// reference implementation.
+    pub fn permute(&self, state: &mut [F; 2]) {
+        for round in 0..self.rounds() {
+            self.round(round, state);
+        }
+        linear_layer(state);
+    }
+
+    /// Round `round` of the permutation: constants, linear layer and S-box.
+    pub fn round(&self, round: usize, state: &mut [F; 2]) {
+        state[0] = state[0] + self.c[round];
+        state[1] = state[1] + self.d[round];
+        linear_layer(state);
+        self.sbox(state);
+    }
+
+    /// The open Flystel: `x -= BETA y^2`, `y -= x^(1/ALPHA)`,
+    /// `x += BETA y^2 + DELTA`.
+    fn sbox(&self, state: &mut [F; 2]) {
+        let [x, y] = state;
+        *x = *x - self.beta * *y * *y;
+        *y = *y - constants::pow(*x, &self.inv_alpha);
+        *x = *x + self.beta * *y * *y + self.delta;
+    }
+}
+
+/// With a single column the MDS matrices are trivial and only the
+/// pseudo-Hadamard transform is left.
+pub fn linear_layer<F: AnemoiField>(state: &mut [F; 2]) {
+    state[1] = state[1] + state[0];
+    state[0] = state[0] + state[1];
+}
+
+/// Hashes `message` over BN254's base field, with the sponge
+/// `AnemoiChip::hash` constrains.
+pub fn hash(message: &[Felt]) -> Felt {
+    instance().hash(message)
+}
+
+/// Jive compression of `left` and `right` over BN254's base field.
+pub fn jive(left: &Felt, right: &Felt) -> Felt {
+    instance().jive(*left, *right)
+}
@@ -0,0 +1,501 @@
+//! Anemoi over the Pallas base field, proven with IPA on Vesta.
+//!
+//! `AnemoiChip` only exists for the Delphinus fork of `halo2_proofs`, which
+//! commits with KZG over BN254 and so cannot prove the base-field instance of
+//! the crate root. To compare Anemoi with Poseidon and MiMC on the same field and
+//! commitment scheme, this module lays out the same single-column instance on
+//! the PSE fork the Poseidon benchmarks use, with round constants derived for
+//! `pallas::Base`, and proves it with any [`Backend`] over that field.
+//!
+//! The sponge gates are shared with [`bn254`](crate::bn254) through
+//! `sponge_gates!`, which also describes the round gate.
+//!
+//! Besides the sponge, [`AnemoiConfig::jive`] lays out the Jive compression:
+//! one permutation of the two inputs, and a last gate adding them to its
+//! output.
+
+use crate::native::{linear_layer, Anemoi};
+use crate::ALPHA;
+use ff::Field;
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::HashBenchmark;
+use halo2curves::pasta::Fp;
+use pse_halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, ProvingKey,
+        Selector, VirtualCells,
+    },
+    poly::Rotation,
+};
+use rand::rngs::OsRng;
+use std::sync::OnceLock;
+use zkwasm_host_circuits::circuits::anemoi::{NUM_HASH_ROUNDS, RATE};
+
+/// Smallest generator of the multiplicative group of `pallas::Base`.
+const GENERATOR: u64 = 5;
+
+/// `1 / ALPHA` modulo `p - 1` for `pallas::Base`.
+const INV_ALPHA: [u64; 4] = [
+    0xe0f0f3f0cccccccd,
+    0x4e9ee0c9a10a60e2,
+    0x3333333333333333,
+    0x3333333333333333,
+];
+
+/// The single-column instance over `pallas::Base`, derived on first use.
+///
+/// The number of rounds only depends on `ALPHA` and the number of columns, so
+/// it is the one of the BN254 instance.
+pub fn instance() -> &'static Anemoi<Fp> {
+    static INSTANCE: OnceLock<Anemoi<Fp>> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        let g = Fp::from(GENERATOR);
+        Anemoi::new(
+            NUM_HASH_ROUNDS,
+            ALPHA as u64,
+            g,
+            g.invert().unwrap(),
+            INV_ALPHA,
+        )
+    })
+}
+
+/// Columns and gates of the sponge, laid out one hash per region.
+#[derive(Debug, Clone)]
+pub struct AnemoiConfig {
+    pub(crate) state: [Column<Advice>; 2],
+    pub(crate) message: Column<Advice>,
+    c: Column<Fixed>,
+    d: Column<Fixed>,
+    pub(crate) expected: Column<Instance>,
+    /// The state on the row is zero.
+    start: Selector,
+    /// `x` on the next row is `x` plus the message element.
+    absorb: Selector,
+    /// The next row is one round away.
+    round: Selector,
+    /// The next row is one linear layer away.
+    linear: Selector,
+    /// `x` on the next row is the sum of the state and the two Jive inputs
+    /// copied next to it.
+    compress: Selector,
+}
+
+impl AnemoiConfig {
+    pub fn configure(meta: &mut ConstraintSystem<Fp>) -> Self {
+        let state = [meta.advice_column(), meta.advice_column()];
+        let message = meta.advice_column();
+        let c = meta.fixed_column();
+        let d = meta.fixed_column();
+        let expected = meta.instance_column();
+        meta.enable_equality(state[0]);
+        meta.enable_equality(state[1]);
+        meta.enable_equality(message);
+        meta.enable_equality(expected);
+
+        let config = Self {
+            state,
+            message,
+            c,
+            d,
+            expected,
+            start: meta.selector(),
+            absorb: meta.selector(),
+            round: meta.selector(),
+            linear: meta.selector(),
+            compress: meta.selector(),
+        };
+        sponge_gates!(
+            meta,
+            config,
+            instance(),
+            |meta: &mut VirtualCells<'_, Fp>, selector| meta.query_selector(selector),
+            |meta: &mut VirtualCells<'_, Fp>, column| meta.query_fixed(column),
+        );
+
+        meta.create_gate("compress", |meta| {
+            let s = meta.query_selector(config.compress);
+            let x = meta.query_advice(state[0], Rotation::cur());
+            let y = meta.query_advice(state[1], Rotation::cur());
+            let left = meta.query_advice(message, Rotation::cur());
+            let right = meta.query_advice(message, Rotation::next());
+            let x_next = meta.query_advice(state[0], Rotation::next());
+            vec![s * (x_next - x - y - left - right)]
+        });
+
+        config
+    }
+
+    /// Lays out the sponge over `len` elements of `message` in its own region,
+    /// and returns the cell holding the digest.
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        message: Value<&[Fp]>,
+        len: usize,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let rows = message.map(sponge_trace);
+        let permutation_rows = instance().rounds() + 2;
+        layouter.assign_region(
+            || "anemoi sponge",
+            |mut region| {
+                self.start.enable(&mut region, 0)?;
+                let mut x = None;
+                for row in 0..1 + len * permutation_rows {
+                    let state = rows.as_ref().map(|rows| rows[row]);
+                    x = Some(self.assign_state(&mut region, row, state)?[0].clone());
+                }
+
+                for i in 0..len {
+                    let offset = i * permutation_rows;
+                    self.absorb.enable(&mut region, offset)?;
+                    region.assign_advice(
+                        || format!("message_{}", i),
+                        self.message,
+                        offset,
+                        || message.map(|message| message[i]),
+                    )?;
+                    self.enable_permutation(&mut region, offset + 1)?;
+                }
+
+                Ok(x.expect("the region has at least the starting row"))
+            },
+        )
+    }
+
+    /// Lays out the Jive compression of `left` and `right` in its own region,
+    /// and returns the cell holding the digest.
+    pub fn jive(
+        &self,
+        layouter: impl Layouter<Fp>,
+        left: Value<Fp>,
+        right: Value<Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let input = left.zip(right).map(|(left, right)| [left, right]);
+        self.jive_with(layouter, input, |region| {
+            self.assign_state(region, 0, input)
+        })
+    }
+
+    /// [`jive`](Self::jive) of two cells assigned in another region, which
+    /// are copied in.
+    pub fn jive_cells(
+        &self,
+        layouter: impl Layouter<Fp>,
+        input: &[AssignedCell<Fp, Fp>; 2],
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let [left, right] = input;
+        let values = left
+            .value()
+            .copied()
+            .zip(right.value().copied())
+            .map(|(left, right)| [left, right]);
+        self.jive_with(layouter, values, |region| {
+            Ok([
+                left.copy_advice(|| "left", region, self.state[0], 0)?,
+                right.copy_advice(|| "right", region, self.state[1], 0)?,
+            ])
+        })
+    }
+
+    /// The Jive region, with `assign_input` placing `input` on the first row.
+    ///
+    /// The inputs start the permutation on the first row and are copied next
+    /// to its output, where one more gate adds up the four.
+    fn jive_with(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        input: Value<[Fp; 2]>,
+        assign_input: impl Fn(&mut Region<'_, Fp>) -> Result<[AssignedCell<Fp, Fp>; 2], Error>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let rows = input.map(permutation_trace);
+        let output = instance().rounds() + 1;
+        layouter.assign_region(
+            || "anemoi jive",
+            |mut region| {
+                let cells = assign_input(&mut region)?;
+                for row in 1..=output {
+                    let state = rows.as_ref().map(|rows| rows[row]);
+                    self.assign_state(&mut region, row, state)?;
+                }
+                self.enable_permutation(&mut region, 0)?;
+
+                for (i, cell) in cells.iter().enumerate() {
+                    cell.copy_advice(
+                        || format!("input_{}", i),
+                        &mut region,
+                        self.message,
+                        output + i,
+                    )?;
+                }
+                self.compress.enable(&mut region, output)?;
+                region.assign_advice(
+                    || "digest",
+                    self.state[0],
+                    output + 1,
+                    || input.map(|[left, right]| instance().jive(left, right)),
+                )
+            },
+        )
+    }
+
+    /// Assigns `state` to `row`.
+    fn assign_state(
+        &self,
+        region: &mut Region<'_, Fp>,
+        row: usize,
+        state: Value<[Fp; 2]>,
+    ) -> Result<[AssignedCell<Fp, Fp>; 2], Error> {
+        let x = region.assign_advice(
+            || format!("x_{}", row),
+            self.state[0],
+            row,
+            || state.map(|state| state[0]),
+        )?;
+        let y = region.assign_advice(
+            || format!("y_{}", row),
+            self.state[1],
+            row,
+            || state.map(|state| state[1]),
+        )?;
+        Ok([x, y])
+    }
+
+    /// Constrains the state from `offset` on to go through the permutation,
+    /// with the result `rounds + 1` rows below.
+    fn enable_permutation(&self, region: &mut Region<'_, Fp>, offset: usize) -> Result<(), Error> {
+        let anemoi = instance();
+        for (round, (&c, &d)) in anemoi.c.iter().zip(&anemoi.d).enumerate() {
+            let offset = offset + round;
+            self.round.enable(region, offset)?;
+            region.assign_fixed(
+                || format!("c_{}", round),
+                self.c,
+                offset,
+                || Value::known(c),
+            )?;
+            region.assign_fixed(
+                || format!("d_{}", round),
+                self.d,
+                offset,
+                || Value::known(d),
+            )?;
+        }
+        self.linear.enable(region, offset + anemoi.rounds())
+    }
+}
+
+/// The state on every row [`AnemoiConfig::hash`] lays out for `message`.
+fn sponge_trace(message: &[Fp]) -> Vec<[Fp; 2]> {
+    let mut rows = vec![[Fp::ZERO; 2]];
+    for element in message {
+        let mut state = *rows.last().unwrap();
+        state[0] += element;
+        rows.extend(permutation_trace(state));
+    }
+    rows
+}
+
+/// `state`, followed by the state after every round and after the final
+/// linear layer.
+fn permutation_trace(mut state: [Fp; 2]) -> Vec<[Fp; 2]> {
+    let anemoi = instance();
+    let mut rows = vec![state];
+    for round in 0..anemoi.rounds() {
+        anemoi.round(round, &mut state);
+        rows.push(state);
+    }
+    linear_layer(&mut state);
+    rows.push(state);
+    rows
+}
+
+/// What each message of an [`AnemoiCircuit`] goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The sponge over messages of this many elements.
+    Sponge(usize),
+    /// Jive compression of two-element messages.
+    Jive,
+}
+
+impl Mode {
+    /// Elements in each message.
+    pub fn message_len(self) -> usize {
+        match self {
+            Mode::Sponge(len) => len,
+            Mode::Jive => 2,
+        }
+    }
+
+    /// Native digest of `message`.
+    pub fn hash(self, message: &[Fp]) -> Fp {
+        match self {
+            Mode::Sponge(_) => instance().hash(message),
+            Mode::Jive => instance().jive(message[0], message[1]),
+        }
+    }
+}
+
+/// Proves that row `i` of the instance column is the digest of the `i`-th of
+/// `hashes` messages under `mode`.
+#[derive(Debug, Clone)]
+pub struct AnemoiCircuit {
+    messages: Value<Vec<Vec<Fp>>>,
+    hashes: usize,
+    mode: Mode,
+}
+
+impl Circuit<Fp> for AnemoiCircuit {
+    type Config = AnemoiConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            messages: Value::unknown(),
+            ..*self
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        AnemoiConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        for i in 0..self.hashes {
+            let namespace = layouter.namespace(|| format!("hash {}", i));
+            let message = self.messages.as_ref().map(|messages| &messages[i][..]);
+            let digest = match self.mode {
+                Mode::Sponge(len) => config.hash(namespace, message, len)?,
+                Mode::Jive => config.jive(
+                    namespace,
+                    message.map(|message| message[0]),
+                    message.map(|message| message[1]),
+                )?,
+            };
+            layouter.constrain_instance(digest.cell(), config.expected, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Anemoi over `pallas::Base`, proven with `B`.
+///
+/// Hashes messages of `RATE` elements with the sponge, as the BN254 benchmark
+/// does, one at a time unless built with [`batch`](Self::batch). A long
+/// message from [`sponge`](Self::sponge) goes through one permutation per
+/// element, while [`jive`](Self::jive) compresses two elements with a single
+/// one, as in a Merkle tree.
+#[derive(Debug, Clone, Copy)]
+pub struct AnemoiBenchmark<B> {
+    backend: B,
+    hashes: usize,
+    mode: Mode,
+}
+
+impl<B: Backend<Field = Fp>> AnemoiBenchmark<B> {
+    /// Proves a single hash.
+    pub fn new(backend: B) -> Self {
+        Self::batch(backend, 1)
+    }
+
+    /// Proves `hashes` independent hashes in one circuit.
+    pub fn batch(backend: B, hashes: usize) -> Self {
+        Self {
+            backend,
+            hashes,
+            mode: Mode::Sponge(RATE),
+        }
+    }
+
+    /// Proves a single sponge hash of a `len`-element message.
+    pub fn sponge(backend: B, len: usize) -> Self {
+        Self {
+            backend,
+            hashes: 1,
+            mode: Mode::Sponge(len),
+        }
+    }
+
+    /// Proves `hashes` independent Jive compressions in one circuit.
+    pub fn jive(backend: B, hashes: usize) -> Self {
+        Self {
+            backend,
+            hashes,
+            mode: Mode::Jive,
+        }
+    }
+
+    fn empty_circuit(&self) -> AnemoiCircuit {
+        AnemoiCircuit {
+            messages: Value::unknown(),
+            hashes: self.hashes,
+            mode: self.mode,
+        }
+    }
+}
+
+impl<B: Backend<Field = Fp>> HashBenchmark for AnemoiBenchmark<B> {
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = Vec<Vec<Fp>>;
+    type Digest = Vec<Fp>;
+    type Circuit = AnemoiCircuit;
+
+    fn name(&self) -> String {
+        let name = match self.mode {
+            Mode::Sponge(RATE) => "anemoi".to_string(),
+            Mode::Sponge(len) => format!("anemoi-sponge-l{}", len),
+            Mode::Jive => "anemoi-jive".to_string(),
+        };
+        match self.hashes {
+            1 => name,
+            n => format!("{}-n{}", name, n),
+        }
+    }
+
+    fn message_len(&self) -> usize {
+        self.mode.message_len()
+    }
+
+    fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    fn random_message(&self) -> Self::Message {
+        (0..self.hashes)
+            .map(|_| {
+                (0..self.mode.message_len())
+                    .map(|_| Fp::random(OsRng))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message
+            .iter()
+            .map(|message| self.mode.hash(message))
+            .collect()
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        AnemoiCircuit {
+            messages: Value::known(message),
+            ..self.empty_circuit()
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        AnemoiCircuit::configure(&mut meta);
+        format!("{:?} {:?}", meta, instance())
+    }
+
+    forward_to_backend!(backend, |digest| digest);
+}
@@ -0,0 +1,151 @@
+//! The BN254 scalar-field circuit must agree with the native hash on any
+//! message, reject a wrong digest, and its KZG proofs must not survive
+//! tampering. The native hashes are pinned to reference results computed
+//! independently of this crate.
+
+use halo2_bench_anemoi::bn254::{instance, AnemoiBenchmark};
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2_proofs::{arithmetic::Field, dev::MockProver, pairing::bn256::Fr};
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use zkwasm_host_circuits::circuits::anemoi::RATE;
+
+const CASES: u32 = 256;
+
+/// Random field elements, with zero, one and minus one drawn far more often
+/// than chance would.
+fn element() -> impl Strategy<Value = Fr> {
+    prop_oneof![
+        1 => Just(Fr::zero()),
+        1 => Just(Fr::one()),
+        1 => Just(-Fr::one()),
+        7 => any::<u64>().prop_map(|seed| Fr::random(StdRng::seed_from_u64(seed))),
+    ]
+}
+
+/// [`min_k`] of `bench`, computed once per benchmark rather than for every
+/// case.
+fn k<B: HashBenchmark>(bench: &B) -> u32 {
+    static K: OnceLock<Mutex<HashMap<(String, &'static str), u32>>> = OnceLock::new();
+    *K.get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry((bench.name(), bench.field()))
+        .or_insert_with(|| min_k(bench).expect("the BN254 instance counts its used rows"))
+}
+
+/// Runs `MockProver` on `bench`'s circuit for `messages` against `digests`.
+fn mock_prove(bench: &AnemoiBenchmark, messages: Vec<[Fr; RATE]>, digests: Vec<Fr>) -> bool {
+    let circuit = bench.circuit(messages, &digests);
+    MockProver::run(k(bench), &circuit, vec![digests])
+        .expect("synthesis should not fail")
+        .verify()
+        .is_ok()
+}
+
+#[test]
+fn native_matches_reference() {
+    let anemoi = instance();
+    let one = Fr::one();
+    let zero = Fr::zero();
+    let counting: Vec<Fr> = (0..8).map(Fr::from).collect();
+    let cases = [
+        (
+            anemoi.hash(&[one, one]),
+            [
+                0x1a6930536d0883bb,
+                0xc89fc7cab03e0761,
+                0xa83b4d8fbc0e4e9b,
+                0x1a59e6103d5e5002,
+            ],
+        ),
+        (
+            anemoi.hash(&[zero, zero]),
+            [
+                0xd3397e479d7aa2e5,
+                0x2baa8840224b5ed2,
+                0x9cea311c47ba4dad,
+                0x21d391954f34db86,
+            ],
+        ),
+        (
+            anemoi.hash(&counting),
+            [
+                0x76dcfcaf2db0d1ec,
+                0x0a0c0e8d4d9828f6,
+                0xb54a864d1f53458e,
+                0x1129501ac133a51f,
+            ],
+        ),
+        (
+            anemoi.jive(one, one),
+            [
+                0x58165d8dc82bde5d,
+                0x46865f2cb1f787ba,
+                0xb0a602e03a92ee1d,
+                0x177d525cd3317d55,
+            ],
+        ),
+    ];
+    for (i, (digest, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(digest, Fr::from_raw(expected), "case {}", i);
+    }
+}
+
+#[test]
+fn rejects_wrong_digest() {
+    let bench = AnemoiBenchmark::batch(3);
+    let messages = bench.random_message();
+    let mut digests = bench.native_hash(&messages);
+    digests[2] += Fr::one();
+    assert!(!mock_prove(&bench, messages, digests));
+}
+
+/// A real proof verifies against its digest only, and flipping a bit anywhere
+/// in it is caught.
+#[test]
+fn proof_rejects_tampering() {
+    let bench = AnemoiBenchmark::new();
+    let k = min_k(&bench).expect("the BN254 instance counts its used rows");
+    let params = bench.setup(k);
+    let pk = bench.keygen(&params);
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    let proof = bench.prove(&params, &pk, bench.circuit(message, &digest), &digest);
+
+    assert!(bench.verify(&params, &pk, &proof, &digest));
+    let wrong = vec![digest[0] + Fr::one()];
+    assert!(!bench.verify(&params, &pk, &proof, &wrong));
+
+    for byte in [0, proof.len() / 2, proof.len() - 1] {
+        let mut tampered = proof.clone();
+        tampered[byte] ^= 1;
+        assert!(
+            !bench.verify(&params, &pk, &tampered, &digest),
+            "flipped byte {} of {} still verifies",
+            byte,
+            proof.len()
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    /// `INV_ALPHA` undoes `x^ALPHA`, so the S-box can be inverted.
+    #[test]
+    fn inv_alpha_inverts_alpha(x in element()) {
+        prop_assert_eq!(x.pow_vartime([5]).pow_vartime(instance().inv_alpha), x);
+    }
+
+    #[test]
+    fn batch_matches_native(
+        messages in prop::collection::vec(prop::array::uniform2(element()), 1..=4),
+    ) {
+        let bench = AnemoiBenchmark::batch(messages.len());
+        let digests = bench.native_hash(&messages);
+        prop_assert!(mock_prove(&bench, messages, digests));
+    }
+}
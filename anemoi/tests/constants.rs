@@ -0,0 +1,281 @@
+//! The derived round constants must be the ones the reference implementation
+//! ships for BN254, which `anemoi/src/lib.rs` used to carry as limbs.
+
+use halo2_bench_anemoi::round_constants;
+use halo2_proofs::pairing::bn256::Fq as Felt;
+use zkwasm_host_circuits::circuits::anemoi::NUM_HASH_ROUNDS;
+
+#[test]
+fn derived_constants_match_reference() {
+    let (c, d) = round_constants();
+    for round in 0..NUM_HASH_ROUNDS {
+        assert_eq!(c[round], Felt::from_raw(C[round]), "C[{}]", round);
+        assert_eq!(d[round], Felt::from_raw(D[round]), "D[{}]", round);
+    }
+}
+
+#[test]
+fn first_constant_is_35() {
+    // g + (1 + 1)^ALPHA with g = 3, ALPHA = 5.
+    assert_eq!(round_constants().0[0], Felt::from(35));
+}
+
+/// `C` as it was pasted from the reference implementation.
+const C: [[u64; 4]; NUM_HASH_ROUNDS] = [
+    [
+        0x0000000000000023,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+    ],
+    [
+        0x775b1f206923c47d,
+        0xa8f87a7963284fbb,
+        0x3bae816d61d6132a,
+        0x0deaee26fa771e0b,
+    ],
+    [
+        0x22a976e6d07d60f5,
+        0xc34df41e5fe46ab6,
+        0x0260d3da3fedf84b,
+        0x2f4c8ca1724196cf,
+    ],
+    [
+        0xc30db64d510ee564,
+        0x95744059950b323d,
+        0x650e5c6bd5cfcb3e,
+        0x05d30d8a084ddd31,
+    ],
+    [
+        0xcd05768f1b651e71,
+        0xad09e76646f8fc45,
+        0xdad4ecd80712fe07,
+        0x21d0c94592289e73,
+    ],
+    [
+        0xeee4a5b31c720775,
+        0xe685edbf1f0f875e,
+        0xecda8089d8eb9a3d,
+        0x0ad405df9e60b24e,
+    ],
+    [
+        0x37019677c912b86e,
+        0xf1b16555957afe6c,
+        0x3631c2568a36f711,
+        0x0cc03a758b72c918,
+    ],
+    [
+        0x433a8db6250f1315,
+        0x16c912d5d9d4ed48,
+        0x7d7c67b60ccdf98d,
+        0x0bc1b9119e2f8d32,
+    ],
+    [
+        0x46cb1f57666d0206,
+        0x8a58a8f27efdb933,
+        0x83e8dd8a41625cd6,
+        0x0fdefd9a0f81cc28,
+    ],
+    [
+        0x8add6292e2e1e661,
+        0xf1f53531867319e8,
+        0x3d6fcf9e000ccbcd,
+        0x0545268f14d1ef2d,
+    ],
+    [
+        0xa2def1f14b524aae,
+        0x275d597192211146,
+        0xa42c1f3e6a1f7e3b,
+        0x304083d89066c255,
+    ],
+    [
+        0x250db07064b2c906,
+        0x1b6b4eeb73bd34db,
+        0x33427c42db863ba9,
+        0x2f6c68f4f14399a4,
+    ],
+    [
+        0xc316649d3fc3381d,
+        0xc0454a7c949fa493,
+        0xafc8f158a8e78784,
+        0x2c253abeaa8f1309,
+    ],
+    [
+        0x28ee11171ca3660e,
+        0xeb197941a15815a9,
+        0x0d6329fa5a982a43,
+        0x28a62c2fcdf31601,
+    ],
+    [
+        0x4e0f7d84e1129058,
+        0x940999a2a073a089,
+        0xcceab807358a652f,
+        0x0b8c0b1fdb7e110a,
+    ],
+    [
+        0x70cf039d739d1046,
+        0xf569e914e8d94eee,
+        0xfb255b7fde25b695,
+        0x1468c3253afd5301,
+    ],
+    [
+        0x3d2a365c3b57cd1b,
+        0x4312821f06af1a11,
+        0xd30bc6c4014eb88e,
+        0x076505a8aac3ed67,
+    ],
+    [
+        0x671b73192354638f,
+        0x07001d04e2195dd2,
+        0xfaeb9a1e631fc8f5,
+        0x1989a97904a6cc74,
+    ],
+    [
+        0xf1e8b99b2fe59e14,
+        0x6c3bcc9fa9f0c3ee,
+        0x7fb680e63a8b45a4,
+        0x0cc2035b47d9bb9e,
+    ],
+    [
+        0xe55901d82eafa11d,
+        0x38694ed7495dc378,
+        0x85acb7120a4ca071,
+        0x218d252816b694c5,
+    ],
+    [
+        0x7b2bfc5a6086dccd,
+        0x2a44cbfa06667305,
+        0xa335ab84fa3fd829,
+        0x0f083607ff8712f6,
+    ],
+];
+
+/// `D` as it was pasted from the reference implementation.
+const D: [[u64; 4]; NUM_HASH_ROUNDS] = [
+    [
+        0xd2c05d64905353a8,
+        0xba56470b9af68708,
+        0xd03583cf0100e593,
+        0x2042def740cbc01b,
+    ],
+    [
+        0x73a2fbd4019568b4,
+        0x5cb6796c004a370d,
+        0xbdd497eaca1967df,
+        0x20e95d0e6735f19a,
+    ],
+    [
+        0xee681bf532a91923,
+        0x8385c5e9d09c9ee3,
+        0x4072d35cf31e5204,
+        0x2b6b3836e5247620,
+    ],
+    [
+        0xf48fc0aaaaad418a,
+        0xf9a5f194fb14170a,
+        0x5fe8df694a1bfe5c,
+        0x03f3ec435fea59d9,
+    ],
+    [
+        0xe014fffb8fc1f126,
+        0x350043a3cf4f64c8,
+        0x2afd01afa94add8c,
+        0x0e760bbfd4bc5260,
+    ],
+    [
+        0x015162795759ef68,
+        0x1727676991bd7352,
+        0x5f43de4dc2cd5c9c,
+        0x148baca8bb5a730c,
+    ],
+    [
+        0x7aad2edb101c1926,
+        0xd96956877d2d5b4b,
+        0x17a23a1d8b446506,
+        0x2dac0dc7fef687d7,
+    ],
+    [
+        0x2fef6d8c3c4c4457,
+        0x8464595c1a04bf97,
+        0xeaff4b1dc220b4c3,
+        0x239dc36bdce081cf,
+    ],
+    [
+        0x8999c35c997fb84e,
+        0x64dadc90d848b6c9,
+        0xe87539e33456eeeb,
+        0x293f0565a6c6611c,
+    ],
+    [
+        0xe7e351515eaddd87,
+        0xa9eaebd37ecda9ba,
+        0x278f2326549a3427,
+        0x1ba459601d9c6c78,
+    ],
+    [
+        0x55f14c562a9a9308,
+        0xd5c8c8fd76fab00b,
+        0x6feeb1125758e33b,
+        0x23f26cea6945ec15,
+    ],
+    [
+        0xe1342a430bee2f41,
+        0xb3f8b199ef4d013d,
+        0x6a9548ebb41718dc,
+        0x0bac5daffbafcd13,
+    ],
+    [
+        0x2aeef4a67c235b35,
+        0xd9977df9611952df,
+        0xb95fbb84b009d6be,
+        0x184e80dc14df31ee,
+    ],
+    [
+        0x968b0e12479b11c9,
+        0x4f7050525593443a,
+        0x4881803e03833ab5,
+        0x2a9075fbd6deb7d8,
+    ],
+    [
+        0xdfcd056b4a53c1d7,
+        0xef8833b711fc7e09,
+        0x495ec0abfeea5d19,
+        0x170c18b10ac6cccf,
+    ],
+    [
+        0x23398e1996ad0ff8,
+        0xf5b7bae9cc1d59bb,
+        0x6612bce50cb34e4d,
+        0x0395e21f8759fb40,
+    ],
+    [
+        0xd623a49cab9c1525,
+        0xf9cde1d0f3a45a16,
+        0x6074c20fa3eb065e,
+        0x00ac9da9488ecdfb,
+    ],
+    [
+        0xfe19c069491f78ba,
+        0x5aa95dc039eb7f8a,
+        0x190f717dfd1a2a1c,
+        0x24fae1a08a518137,
+    ],
+    [
+        0x5c3ac9fb10b98b6c,
+        0x78709792f130f84b,
+        0xaba2e0fff5246b1c,
+        0x03428dc08c3b8b36,
+    ],
+    [
+        0x96e4cea20b00019d,
+        0x7f47df444750efae,
+        0x4accf8d91870397b,
+        0x2d2a504d0f8d8c1e,
+    ],
+    [
+        0xe68abccf2b3601a8,
+        0x8dc73527bccb9ec2,
+        0xd126f57ec2b39998,
+        0x2ef503c0bb8962e7,
+    ],
+];
@@ -0,0 +1,56 @@
+//! The circuit must agree with [`native::hash`] on any message, and the native
+//! hash with the known test vectors.
+
+use halo2_bench_anemoi::{min_k, native, test_circuits, test_vector, BatchCircuit};
+use halo2_proofs::{arithmetic::Field, dev::MockProver, pairing::bn256::Fq as Felt};
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::OnceLock;
+
+const CASES: u32 = 256;
+
+/// Most messages hashed in one circuit.
+const MAX_BATCH: usize = 4;
+
+/// Rows for every case, probed once as `min_k` runs `MockProver` itself.
+fn k() -> u32 {
+    static K: OnceLock<u32> = OnceLock::new();
+    *K.get_or_init(|| min_k(MAX_BATCH))
+}
+
+/// Random field elements, with zero, one and minus one drawn far more often
+/// than chance would.
+fn element() -> impl Strategy<Value = Felt> {
+    prop_oneof![
+        1 => Just(Felt::zero()),
+        1 => Just(Felt::one()),
+        1 => Just(-Felt::one()),
+        7 => any::<u64>().prop_map(|seed| Felt::random(StdRng::seed_from_u64(seed))),
+    ]
+}
+
+#[test]
+fn native_matches_test_vectors() {
+    for circuit in test_circuits() {
+        assert_eq!(native::hash(&circuit.inputs), circuit.result);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn hash_matches_native(inputs in prop::array::uniform2(element())) {
+        let prover = MockProver::run(k(), &test_vector(inputs), vec![]).unwrap();
+        prop_assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn batch_matches_native(
+        messages in prop::collection::vec(prop::array::uniform2(element()), 1..=MAX_BATCH),
+    ) {
+        let batch = BatchCircuit(messages.into_iter().map(test_vector).collect());
+        let prover = MockProver::run(k(), &batch, vec![]).unwrap();
+        prop_assert_eq!(prover.verify(), Ok(()));
+    }
+}
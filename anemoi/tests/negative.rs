@@ -0,0 +1,58 @@
+//! The circuit must reject a wrong result, not only accept the right one.
+//!
+//! The base-field instance cannot be proven with KZG on BN254, so it is only
+//! checked with `MockProver`; `tests/bn254.rs` tampers with proofs of the
+//! scalar-field one.
+
+use halo2_bench_anemoi::{circuit_stats, min_k, random_circuit, test_circuits, BatchCircuit};
+use halo2_proofs::{dev::MockProver, pairing::bn256::Fq as Felt};
+
+#[test]
+fn accepts_test_vectors() {
+    let k = min_k(1);
+    for circuit in test_circuits() {
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn rejects_wrong_result() {
+    let k = min_k(1);
+    for mut circuit in test_circuits() {
+        circuit.result += Felt::one();
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn rejects_wrong_input() {
+    let k = min_k(1);
+    for mut circuit in test_circuits() {
+        circuit.inputs[0] += Felt::one();
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn batch_rejects_one_wrong_result() {
+    let k = min_k(3);
+    for circuit in test_circuits() {
+        let mut batch = BatchCircuit(vec![circuit; 3]);
+        batch.0[2].result += Felt::one();
+        let prover = MockProver::run(k, &batch, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+/// Every `k` below `min_k`, down to the fewest rows halo2 allows, must be
+/// reported as not fitting rather than panic during synthesis.
+#[test]
+fn undersized_k_is_an_error() {
+    let circuit = random_circuit();
+    for k in circuit_stats().k_for(1)..min_k(1) {
+        assert!(MockProver::run(k, &circuit, vec![]).is_err(), "k = {}", k);
+    }
+}
@@ -0,0 +1,197 @@
+//! The Pasta circuits must agree with the native hash on any message, in the
+//! sponge and the Jive mode and along Merkle paths, and reject a wrong digest.
+//! The native hashes are pinned to reference results computed independently
+//! of this crate.
+
+use ff::Field;
+use halo2_bench_anemoi::merkle::{self, AnemoiMerkleBenchmark};
+use halo2_bench_anemoi::pasta::{instance, AnemoiBenchmark};
+use halo2_bench_backend::Ipa;
+use halo2_bench_harness::merkle::MerklePath;
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2curves::pasta::Fp;
+use proptest::prelude::*;
+use pse_halo2_proofs::dev::MockProver;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const CASES: u32 = 256;
+
+/// Random field elements, with zero, one and minus one drawn far more often
+/// than chance would.
+fn element() -> impl Strategy<Value = Fp> {
+    prop_oneof![
+        1 => Just(Fp::ZERO),
+        1 => Just(Fp::ONE),
+        1 => Just(-Fp::ONE),
+        7 => any::<u64>().prop_map(|seed| Fp::random(StdRng::seed_from_u64(seed))),
+    ]
+}
+
+fn message() -> impl Strategy<Value = Vec<Fp>> {
+    prop::collection::vec(element(), 2)
+}
+
+/// Paths of up to `max_depth` levels with arbitrary siblings.
+fn path(max_depth: usize) -> impl Strategy<Value = MerklePath<Fp>> {
+    let siblings = prop::collection::vec(element(), 1..=max_depth);
+    (element(), any::<u64>(), siblings).prop_map(|(leaf, index, siblings)| MerklePath {
+        leaf,
+        index: index & ((1 << siblings.len()) - 1),
+        siblings,
+    })
+}
+
+/// [`min_k`] of `bench`, computed once per benchmark rather than for every
+/// case.
+fn k<B: HashBenchmark>(bench: &B) -> u32 {
+    static K: OnceLock<Mutex<HashMap<(String, &'static str), u32>>> = OnceLock::new();
+    *K.get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry((bench.name(), bench.field()))
+        .or_insert_with(|| min_k(bench).expect("the Pasta instance reports its used rows"))
+}
+
+/// Runs `MockProver` on the path circuit for `path` against `root`.
+fn mock_prove_path(path: MerklePath<Fp>, root: Fp) -> bool {
+    let bench = AnemoiMerkleBenchmark::new(Ipa, path.depth());
+    let circuit = bench.circuit(path, &root);
+    MockProver::run(k(&bench), &circuit, vec![vec![root]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+/// Checks with `MockProver` that `bench`'s circuit for `messages` accepts the
+/// native digests.
+fn agrees(bench: &AnemoiBenchmark<Ipa>, messages: Vec<Vec<Fp>>) -> Result<(), TestCaseError> {
+    let digests = bench.native_hash(&messages);
+    let circuit = bench.circuit(messages, &digests);
+    let prover = MockProver::run(k(bench), &circuit, vec![digests]).unwrap();
+    prop_assert_eq!(prover.verify(), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn native_matches_reference() {
+    let anemoi = instance();
+    let one = Fp::ONE;
+    let zero = Fp::ZERO;
+    let counting: Vec<Fp> = (0..8).map(Fp::from).collect();
+    let cases = [
+        (
+            anemoi.hash(&[one, one]),
+            [
+                0x58044152c75f525a,
+                0xf42a6ac8d9340f5d,
+                0x27c7df1801d04059,
+                0x161dbd84d0d94cca,
+            ],
+        ),
+        (
+            anemoi.hash(&[zero, zero]),
+            [
+                0x904e8b853132a06a,
+                0x99e9694392cab286,
+                0xfcc1b0aa11b95808,
+                0x3dbb3066d47387e9,
+            ],
+        ),
+        (
+            anemoi.hash(&counting),
+            [
+                0x61d4b8784471c186,
+                0x4f6a575331a05ac3,
+                0x87c767bb7b8b2510,
+                0x1fc6c27951f9b639,
+            ],
+        ),
+        (
+            anemoi.jive(one, one),
+            [
+                0xde4956587c330569,
+                0xf21bd895011aea13,
+                0xe7f41e4be6dfca01,
+                0x1ec9ade59116709d,
+            ],
+        ),
+        (
+            anemoi.jive(zero, zero),
+            [
+                0x591b876e084b3fbd,
+                0x137b9f6696040792,
+                0xb0e588f370be60ac,
+                0x3805ed1d8a472982,
+            ],
+        ),
+    ];
+    for (i, (digest, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(digest, Fp::from_raw(expected), "case {}", i);
+    }
+}
+
+#[test]
+fn rejects_wrong_digest() {
+    for bench in [AnemoiBenchmark::new(Ipa), AnemoiBenchmark::jive(Ipa, 1)] {
+        let k = min_k(&bench).expect("the Pasta instance reports its used rows");
+        let message = bench.random_message();
+        let mut digests = bench.native_hash(&message);
+        let circuit = bench.circuit(message, &digests);
+        digests[0] += Fp::ONE;
+        let prover = MockProver::run(k, &circuit, vec![digests]).unwrap();
+        assert!(prover.verify().is_err(), "{}", bench.name());
+    }
+}
+
+#[test]
+fn merkle_rejects_wrong_path() {
+    let bench = AnemoiMerkleBenchmark::new(Ipa, 4);
+    let path = bench.random_message();
+    let root = bench.native_hash(&path);
+    assert!(mock_prove_path(path.clone(), root));
+    assert!(!mock_prove_path(path.clone(), root + Fp::ONE));
+
+    let mut wrong_sibling = path.clone();
+    wrong_sibling.siblings[2] += Fp::ONE;
+    assert!(!mock_prove_path(wrong_sibling, root));
+
+    // Flipping an index bit swaps a pair, which Jive is not symmetric in
+    // unless the two are equal.
+    let mut wrong_index = path.clone();
+    wrong_index.index ^= 1;
+    assert_ne!(path.leaf, path.siblings[0]);
+    assert!(!mock_prove_path(wrong_index, root));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    /// `INV_ALPHA` undoes `x^ALPHA`, so the S-box can be inverted.
+    #[test]
+    fn inv_alpha_inverts_alpha(x in element()) {
+        prop_assert_eq!(x.pow_vartime([5]).pow_vartime(instance().inv_alpha), x);
+    }
+
+    #[test]
+    fn batch_matches_native(messages in prop::collection::vec(message(), 1..=4)) {
+        agrees(&AnemoiBenchmark::batch(Ipa, messages.len()), messages)?;
+    }
+
+    #[test]
+    fn sponge_matches_native(message in prop::collection::vec(element(), 1..=9)) {
+        agrees(&AnemoiBenchmark::sponge(Ipa, message.len()), vec![message])?;
+    }
+
+    #[test]
+    fn jive_matches_native(messages in prop::collection::vec(message(), 1..=4)) {
+        agrees(&AnemoiBenchmark::jive(Ipa, messages.len()), messages)?;
+    }
+
+    #[test]
+    fn merkle_matches_native(path in path(8)) {
+        let root = path.root(merkle::compress);
+        prop_assert!(mock_prove_path(path, root));
+    }
+}
@@ -0,0 +1,71 @@
+//! `constraint_stats` reads the layout out of the `Debug` dump, as the
+//! Delphinus fork keeps the counts crate-private. A change to that dump must
+//! fail here rather than skew the reported stats.
+
+use halo2_bench_anemoi::constraint_stats;
+use halo2_bench_harness::CircuitStats;
+use halo2_proofs::{
+    circuit::{floor_planner::FlatFloorPlanner, Layouter},
+    pairing::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
+    poly::Rotation,
+};
+
+/// Two advice, one fixed and one instance column, two gates holding three
+/// constraints, the first of degree 5, and one lookup.
+#[derive(Debug, Clone, Copy, Default)]
+struct Probe;
+
+impl Circuit<Fr> for Probe {
+    type Config = ();
+    type FloorPlanner = FlatFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Probe
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let fixed = meta.fixed_column();
+        meta.instance_column();
+        let table = meta.lookup_table_column();
+
+        meta.create_gate("degree 5", |meta| {
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![a.clone() * a.clone() * a.clone() * a * b]
+        });
+        meta.create_gate("two constraints", |meta| {
+            let cur = meta.query_advice(a, Rotation::cur());
+            let next = meta.query_advice(a, Rotation::next());
+            let fixed = meta.query_fixed(fixed, Rotation::cur());
+            vec![cur.clone() - next, cur - fixed]
+        });
+        meta.lookup("probe", |meta| {
+            vec![(meta.query_advice(b, Rotation::cur()), table)]
+        });
+    }
+
+    fn synthesize(&self, _config: (), _layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn counts_match_probe() {
+    let stats = constraint_stats::<Fr, Probe>();
+    assert_eq!(
+        stats,
+        CircuitStats {
+            advice_columns: 2,
+            fixed_columns: 1,
+            instance_columns: 1,
+            gates: 2,
+            lookups: 1,
+            max_degree: 5,
+            used_rows: None,
+            blinding_factors: stats.blinding_factors,
+        }
+    );
+}
@@ -0,0 +1,481 @@
+//! Commitment schemes the circuits laid out on PSE `halo2_proofs` can be
+//! proven with.
+//!
+//! PSE `halo2_proofs` ships IPA over the Pasta cycle and KZG over BN254, the
+//! latter with two multiopen arguments (GWC and SHPLONK). Each is exposed as a
+//! zero-sized [`Backend`] so the benchmarks stay generic over both the scheme
+//! and the field the circuit is defined over. Poseidon and the Pasta instance
+//! of Anemoi share them, together with [`forward_to_backend!`] for the
+//! [`HashBenchmark`](halo2_bench_harness::HashBenchmark) methods that only
+//! pass through.
+
+use ff::{FromUniformBytes, PrimeFieldBits, WithSmallOrderMulGroup};
+use halo2_bench_harness::{CircuitStats, KeySizes};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem, ProvingKey,
+    },
+    poly::{
+        commitment::{Params, ParamsProver},
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::SingleStrategy as IpaStrategy,
+        },
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverGWC, ProverSHPLONK, VerifierGWC, VerifierSHPLONK},
+            strategy::SingleStrategy as KzgStrategy,
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+    SerdeFormat,
+};
+use halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2curves::group::GroupEncoding;
+use halo2curves::pasta::{EqAffine, Fp};
+use rand::rngs::OsRng;
+use std::fmt::Debug;
+use std::io;
+
+mod rows;
+
+#[doc(hidden)]
+pub use halo2_bench_harness as harness;
+
+/// A polynomial commitment scheme together with its prover and verifier.
+pub trait Backend: Debug + Clone + Copy {
+    /// Field the circuit is defined over, with what keygen and the Poseidon
+    /// constants generation ask of it.
+    type Field: FromUniformBytes<64> + PrimeFieldBits + Ord + WithSmallOrderMulGroup<3> + 'static;
+    /// Curve whose scalar field is [`Self::Field`].
+    type Curve: CurveAffine<ScalarExt = Self::Field>;
+    /// Commitment parameters.
+    type Params;
+
+    /// Scheme as reported, e.g. `kzg-gwc`.
+    fn name(&self) -> &'static str;
+
+    /// Curve the commitments live on, e.g. `bn254`.
+    fn curve(&self) -> &'static str;
+
+    /// Field the circuit is defined over, e.g. `bn254-scalar`.
+    fn field(&self) -> &'static str;
+
+    /// Layout of `circuit` on `2^k` rows.
+    fn circuit_stats<C: Circuit<Self::Field>>(&self, k: u32, circuit: &C) -> CircuitStats {
+        circuit_stats::<Self::Curve, C>(k, circuit)
+    }
+
+    /// Generates parameters for `2^k` rows.
+    fn setup(&self, k: u32) -> Self::Params;
+
+    /// Generates the proving key of `circuit`.
+    fn keygen<C: Circuit<Self::Field>>(
+        &self,
+        params: &Self::Params,
+        circuit: &C,
+    ) -> ProvingKey<Self::Curve>;
+
+    /// Proves `circuit` against a single instance column holding `instance`.
+    fn prove<C: Circuit<Self::Field>>(
+        &self,
+        params: &Self::Params,
+        pk: &ProvingKey<Self::Curve>,
+        circuit: C,
+        instance: &[Self::Field],
+    ) -> Vec<u8>;
+
+    /// Checks `proof` against the verifying key held by `pk`.
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &ProvingKey<Self::Curve>,
+        proof: &[u8],
+        instance: &[Self::Field],
+    ) -> bool;
+
+    /// Serialized sizes of `params` and of the keys in `pk`.
+    fn key_sizes(&self, params: &Self::Params, pk: &ProvingKey<Self::Curve>) -> KeySizes;
+
+    /// Serializes `params` with `Params::write`.
+    fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>>;
+
+    /// Reads back what [`params_to_bytes`](Self::params_to_bytes) wrote.
+    fn params_from_bytes(&self, bytes: &[u8]) -> io::Result<Self::Params>;
+
+    /// Whether [`pk_to_bytes`](Self::pk_to_bytes) can serialize a proving
+    /// key on this curve.
+    fn supports_pk_cache(&self) -> bool;
+
+    /// Serializes `pk`, failing with [`io::ErrorKind::Unsupported`] if the
+    /// curve has no `SerdeFormat` encoding.
+    fn pk_to_bytes(&self, pk: &ProvingKey<Self::Curve>) -> io::Result<Vec<u8>>;
+
+    /// Reads back a proving key of `C` written by
+    /// [`pk_to_bytes`](Self::pk_to_bytes).
+    fn pk_from_bytes<C: Circuit<Self::Field>>(
+        &self,
+        bytes: &[u8],
+    ) -> io::Result<ProvingKey<Self::Curve>>;
+}
+
+/// Layout of `circuit` with commitments on `G`.
+pub fn circuit_stats<G: CurveAffine, C: Circuit<G::ScalarExt>>(
+    k: u32,
+    circuit: &C,
+) -> CircuitStats {
+    let mut meta = ConstraintSystem::<G::ScalarExt>::default();
+    C::configure(&mut meta);
+    CircuitStats {
+        advice_columns: meta.num_advice_columns(),
+        fixed_columns: meta.num_fixed_columns(),
+        instance_columns: meta.num_instance_columns(),
+        gates: meta.gates().len(),
+        lookups: meta.lookups().len(),
+        max_degree: meta.degree(),
+        used_rows: Some(rows::used_rows(k, circuit)),
+        blinding_factors: meta.blinding_factors(),
+    }
+}
+
+/// Implements the [`HashBenchmark`](halo2_bench_harness::HashBenchmark)
+/// methods that only pass through to a [`Backend`]: everything but the
+/// associated types, `name`, `message_len`, `hashes`, `random_message`,
+/// `native_hash`, `circuit` and `fingerprint`.
+///
+/// `$backend` names the field holding the backend. The type must have an
+/// `empty_circuit` method returning its circuit without witnesses, and
+/// `|$digest| $instance` turns `&Self::Digest` into the instance column.
+#[macro_export]
+macro_rules! forward_to_backend {
+    ($backend:tt, |$digest:ident| $instance:expr) => {
+        fn curve(&self) -> &'static str {
+            self.$backend.curve()
+        }
+
+        fn field(&self) -> &'static str {
+            self.$backend.field()
+        }
+
+        fn backend(&self) -> &'static str {
+            self.$backend.name()
+        }
+
+        fn circuit_stats(&self, k: u32) -> $crate::harness::CircuitStats {
+            self.$backend.circuit_stats(k, &self.empty_circuit())
+        }
+
+        fn setup(&self, k: u32) -> Self::Params {
+            self.$backend.setup(k)
+        }
+
+        fn keygen(&self, params: &Self::Params) -> Self::ProvingKey {
+            self.$backend.keygen(params, &self.empty_circuit())
+        }
+
+        fn prove(
+            &self,
+            params: &Self::Params,
+            pk: &Self::ProvingKey,
+            circuit: Self::Circuit,
+            digest: &Self::Digest,
+        ) -> Vec<u8> {
+            let $digest = digest;
+            self.$backend.prove(params, pk, circuit, $instance)
+        }
+
+        fn verify(
+            &self,
+            params: &Self::Params,
+            pk: &Self::ProvingKey,
+            proof: &[u8],
+            digest: &Self::Digest,
+        ) -> bool {
+            let $digest = digest;
+            self.$backend.verify(params, pk, proof, $instance)
+        }
+
+        fn key_sizes(
+            &self,
+            params: &Self::Params,
+            pk: &Self::ProvingKey,
+        ) -> $crate::harness::KeySizes {
+            self.$backend.key_sizes(params, pk)
+        }
+
+        fn params_to_bytes(&self, params: &Self::Params) -> std::io::Result<Vec<u8>> {
+            self.$backend.params_to_bytes(params)
+        }
+
+        fn params_from_bytes(&self, bytes: &[u8]) -> std::io::Result<Self::Params> {
+            self.$backend.params_from_bytes(bytes)
+        }
+
+        fn supports_pk_cache(&self) -> bool {
+            self.$backend.supports_pk_cache()
+        }
+
+        fn pk_to_bytes(&self, pk: &Self::ProvingKey) -> std::io::Result<Vec<u8>> {
+            self.$backend.pk_to_bytes(pk)
+        }
+
+        fn pk_from_bytes(
+            &self,
+            _params: &Self::Params,
+            bytes: &[u8],
+        ) -> std::io::Result<Self::ProvingKey> {
+            self.$backend.pk_from_bytes::<Self::Circuit>(bytes)
+        }
+    };
+}
+
+fn write_params<'params, C: CurveAffine, P: Params<'params, C>>(params: &P) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    params.write(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn params_size<'params, C: CurveAffine, P: Params<'params, C>>(params: &P) -> usize {
+    write_params(params)
+        .expect("writing to a Vec should not fail")
+        .len()
+}
+
+/// IPA over Pasta: circuits over the Pallas base field, commitments on Vesta.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ipa;
+
+impl Backend for Ipa {
+    type Field = Fp;
+    type Curve = EqAffine;
+    type Params = ParamsIPA<EqAffine>;
+
+    fn name(&self) -> &'static str {
+        "ipa"
+    }
+
+    fn curve(&self) -> &'static str {
+        "vesta"
+    }
+
+    fn field(&self) -> &'static str {
+        "pallas-base"
+    }
+
+    fn setup(&self, k: u32) -> Self::Params {
+        ParamsIPA::new(k)
+    }
+
+    fn keygen<C: Circuit<Fp>>(&self, params: &Self::Params, circuit: &C) -> ProvingKey<EqAffine> {
+        let vk = keygen_vk(params, circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, circuit).expect("keygen_pk should not fail")
+    }
+
+    fn prove<C: Circuit<Fp>>(
+        &self,
+        params: &Self::Params,
+        pk: &ProvingKey<EqAffine>,
+        circuit: C,
+        instance: &[Fp],
+    ) -> Vec<u8> {
+        let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+        create_proof::<IPACommitmentScheme<_>, ProverIPA<_>, _, _, _, _>(
+            params,
+            pk,
+            &[circuit],
+            &[&[instance]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &ProvingKey<EqAffine>,
+        proof: &[u8],
+        instance: &[Fp],
+    ) -> bool {
+        let strategy = IpaStrategy::new(params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof::<IPACommitmentScheme<_>, VerifierIPA<_>, _, _, _>(
+            params,
+            pk.get_vk(),
+            strategy,
+            &[&[instance]],
+            &mut transcript,
+        )
+        .is_ok()
+    }
+
+    /// Pasta points implement no `SerdeCurveAffine`, so the keys cannot go
+    /// through `SerdeFormat` and neither is measured. The VK's compressed
+    /// commitments, which is all a verifier needs besides the circuit
+    /// itself, are reported as its estimate instead.
+    fn key_sizes(&self, params: &Self::Params, pk: &ProvingKey<EqAffine>) -> KeySizes {
+        let vk = pk.get_vk();
+        let commitments = vk.fixed_commitments().len() + vk.permutation().commitments().len();
+        let point = <EqAffine as GroupEncoding>::Repr::default().as_ref().len();
+        KeySizes {
+            params: Some(params_size(params)),
+            vk: None,
+            pk: None,
+            vk_estimate: Some(commitments * point),
+        }
+    }
+
+    fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>> {
+        write_params(params)
+    }
+
+    fn params_from_bytes(&self, mut bytes: &[u8]) -> io::Result<Self::Params> {
+        ParamsIPA::read(&mut bytes)
+    }
+
+    /// See [`key_sizes`](Self::key_sizes): the keys cannot be serialized.
+    fn supports_pk_cache(&self) -> bool {
+        false
+    }
+
+    fn pk_to_bytes(&self, _pk: &ProvingKey<EqAffine>) -> io::Result<Vec<u8>> {
+        Err(halo2_bench_harness::cache::unsupported())
+    }
+
+    fn pk_from_bytes<C: Circuit<Fp>>(&self, _bytes: &[u8]) -> io::Result<ProvingKey<EqAffine>> {
+        Err(halo2_bench_harness::cache::unsupported())
+    }
+}
+
+/// Implements [`Backend`] for KZG over BN254 with the given multiopen argument.
+macro_rules! kzg_backend {
+    ($(#[$doc:meta])* $backend:ident, $name:literal, $prover:ident, $verifier:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $backend;
+
+        impl Backend for $backend {
+            type Field = Fr;
+            type Curve = G1Affine;
+            type Params = ParamsKZG<Bn256>;
+
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn curve(&self) -> &'static str {
+                "bn254"
+            }
+
+            fn field(&self) -> &'static str {
+                "bn254-scalar"
+            }
+
+            fn setup(&self, k: u32) -> Self::Params {
+                ParamsKZG::setup(k, OsRng)
+            }
+
+            fn keygen<C: Circuit<Fr>>(
+                &self,
+                params: &Self::Params,
+                circuit: &C,
+            ) -> ProvingKey<G1Affine> {
+                let vk = keygen_vk(params, circuit).expect("keygen_vk should not fail");
+                keygen_pk(params, vk, circuit).expect("keygen_pk should not fail")
+            }
+
+            fn prove<C: Circuit<Fr>>(
+                &self,
+                params: &Self::Params,
+                pk: &ProvingKey<G1Affine>,
+                circuit: C,
+                instance: &[Fr],
+            ) -> Vec<u8> {
+                let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+                create_proof::<KZGCommitmentScheme<Bn256>, $prover<_>, _, _, _, _>(
+                    params,
+                    pk,
+                    &[circuit],
+                    &[&[instance]],
+                    OsRng,
+                    &mut transcript,
+                )
+                .expect("proof generation should not fail");
+                transcript.finalize()
+            }
+
+            fn verify(
+                &self,
+                params: &Self::Params,
+                pk: &ProvingKey<G1Affine>,
+                proof: &[u8],
+                instance: &[Fr],
+            ) -> bool {
+                let strategy = KzgStrategy::new(params);
+                let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+                verify_proof::<KZGCommitmentScheme<Bn256>, $verifier<_>, _, _, _>(
+                    params,
+                    pk.get_vk(),
+                    strategy,
+                    &[&[instance]],
+                    &mut transcript,
+                )
+                .is_ok()
+            }
+
+            fn key_sizes(&self, params: &Self::Params, pk: &ProvingKey<G1Affine>) -> KeySizes {
+                KeySizes {
+                    params: Some(params_size(params)),
+                    vk: Some(pk.get_vk().to_bytes(SerdeFormat::Processed).len()),
+                    pk: Some(pk.to_bytes(SerdeFormat::Processed).len()),
+                    vk_estimate: None,
+                }
+            }
+
+            fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>> {
+                write_params(params)
+            }
+
+            fn params_from_bytes(&self, mut bytes: &[u8]) -> io::Result<Self::Params> {
+                ParamsKZG::read(&mut bytes)
+            }
+
+            fn supports_pk_cache(&self) -> bool {
+                true
+            }
+
+            fn pk_to_bytes(&self, pk: &ProvingKey<G1Affine>) -> io::Result<Vec<u8>> {
+                Ok(pk.to_bytes(SerdeFormat::Processed))
+            }
+
+            fn pk_from_bytes<C: Circuit<Fr>>(
+                &self,
+                mut bytes: &[u8],
+            ) -> io::Result<ProvingKey<G1Affine>> {
+                ProvingKey::read::<_, C>(&mut bytes, SerdeFormat::Processed)
+            }
+        }
+    };
+}
+
+kzg_backend!(
+    /// KZG over BN254 with the GWC multiopen argument.
+    KzgGwc,
+    "kzg-gwc",
+    ProverGWC,
+    VerifierGWC
+);
+
+kzg_backend!(
+    /// KZG over BN254 with the SHPLONK multiopen argument.
+    KzgShplonk,
+    "kzg-shplonk",
+    ProverSHPLONK,
+    VerifierSHPLONK
+);
@@ -0,0 +1,146 @@
+//! On-disk cache of params and proving keys.
+//!
+//! With a cache, [`run`](crate::run) generates params and keys at most once
+//! per circuit, backend and `k`, so the param and keygen phases measure
+//! deserialization and proving can be benchmarked on its own. Proving keys
+//! are only cached where [`HashBenchmark::supports_pk_cache`] holds.
+//!
+//! An entry is the directory `<dir>/<name>-<backend>-k<k>` holding
+//! `params.bin` and `pk.bin`; the proving key embeds the verifying key. Every
+//! file starts with a hash of [`HashBenchmark::fingerprint`], and files whose
+//! hash does not match the current circuit are treated as missing.
+
+use crate::HashBenchmark;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Cache files of one benchmark at one `k`.
+pub(crate) struct Entry {
+    params: PathBuf,
+    pk: PathBuf,
+    tag: [u8; 8],
+}
+
+/// What an [`Entry`] holds after [`Entry::populate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Contents {
+    pub params: bool,
+    pub pk: bool,
+    /// Time spent serializing and writing, if anything had to be written.
+    pub write: Option<Duration>,
+}
+
+impl Entry {
+    pub fn new<B: HashBenchmark>(dir: &Path, bench: &B, k: u32) -> Self {
+        let dir = dir.join(format!("{}-{}-k{}", bench.name(), bench.backend(), k));
+        // `DefaultHasher` may change between Rust releases, which at worst
+        // invalidates the cache.
+        let mut hasher = DefaultHasher::new();
+        bench.fingerprint().hash(&mut hasher);
+        Self {
+            params: dir.join("params.bin"),
+            pk: dir.join("pk.bin"),
+            tag: hasher.finish().to_le_bytes(),
+        }
+    }
+
+    /// Makes sure the entry holds whatever `bench` can serialize.
+    ///
+    /// Params are regenerated together with the proving key, since a key is
+    /// only valid for the params it was generated with. Keygen is skipped
+    /// when `bench` cannot serialize the key.
+    pub fn populate<B: HashBenchmark>(&self, bench: &B, k: u32) -> io::Result<Contents> {
+        let pk_cached = bench.supports_pk_cache();
+        let mut contents = Contents {
+            params: self.read(&self.params)?.is_some(),
+            pk: pk_cached && self.read(&self.pk)?.is_some(),
+            write: None,
+        };
+        if contents.params && (contents.pk || !pk_cached) {
+            return Ok(contents);
+        }
+
+        let params = if contents.params {
+            self.load_params(bench)?
+        } else {
+            bench.setup(k)
+        };
+        let pk = pk_cached.then(|| bench.keygen(&params));
+
+        let start = Instant::now();
+        let mut written = false;
+        if !contents.params {
+            contents.params = self.store(&self.params, bench.params_to_bytes(&params))?;
+            written |= contents.params;
+        }
+        if let Some(pk) = pk {
+            contents.pk = self.store(&self.pk, bench.pk_to_bytes(&pk))?;
+            written |= contents.pk;
+        }
+        contents.write = written.then(|| start.elapsed());
+        Ok(contents)
+    }
+
+    pub fn load_params<B: HashBenchmark>(&self, bench: &B) -> io::Result<B::Params> {
+        let bytes = self
+            .read(&self.params)?
+            .ok_or_else(|| missing(&self.params))?;
+        bench.params_from_bytes(&bytes)
+    }
+
+    pub fn load_pk<B: HashBenchmark>(
+        &self,
+        bench: &B,
+        params: &B::Params,
+    ) -> io::Result<B::ProvingKey> {
+        let bytes = self.read(&self.pk)?.ok_or_else(|| missing(&self.pk))?;
+        bench.pk_from_bytes(params, &bytes)
+    }
+
+    /// Payload of `path`, or `None` if it is missing or stale.
+    fn read(&self, path: &Path) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(path) {
+            Ok(mut bytes) if bytes.starts_with(&self.tag) => {
+                bytes.drain(..self.tag.len());
+                Ok(Some(bytes))
+            }
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes `bytes` to `path`, returning `false` if the flavour cannot
+    /// serialize it.
+    fn store(&self, path: &Path, bytes: io::Result<Vec<u8>>) -> io::Result<bool> {
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::Unsupported => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, [&self.tag[..], &bytes].concat())?;
+        Ok(true)
+    }
+}
+
+fn missing(path: &Path) -> io::Error {
+    io::Error::new(
+        ErrorKind::NotFound,
+        format!("{} is missing or stale", path.display()),
+    )
+}
+
+/// Error returned by the default serialization methods of [`HashBenchmark`].
+pub fn unsupported() -> io::Error {
+    io::Error::new(
+        ErrorKind::Unsupported,
+        "this halo2_proofs flavour cannot serialize it",
+    )
+}
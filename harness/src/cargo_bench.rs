@@ -0,0 +1,55 @@
+//! Criterion groups shared by the `cargo bench` targets of the bench crates.
+//!
+//! Params and keys are generated once per `k` outside the measured loop, so
+//! the groups isolate proving and verification.
+
+use crate::HashBenchmark;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+
+/// Benchmarks `create_proof` and `verify_proof` of `bench` for every `k`.
+///
+/// Results land in the `<name>/<backend>/prove` and `<name>/<backend>/verify`
+/// groups, with one entry per `k`. Proving throughput is counted in hashes, so
+/// batched circuits report the amortized rate.
+pub fn prove_and_verify<B: HashBenchmark>(c: &mut Criterion, bench: &B, ks: &[u32]) {
+    let name = format!("{}/{}", bench.name(), bench.backend());
+    let keys = ks
+        .iter()
+        .map(|&k| {
+            let params = bench.setup(k);
+            let pk = bench.keygen(&params);
+            (k, params, pk)
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group(format!("{}/prove", name));
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(bench.hashes() as u64));
+    for (k, params, pk) in &keys {
+        group.bench_with_input(BenchmarkId::from_parameter(k), k, |b, _| {
+            b.iter_batched(
+                || witness(bench),
+                |(circuit, digest)| bench.prove(params, pk, circuit, &digest),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("{}/verify", name));
+    for (k, params, pk) in &keys {
+        let (circuit, digest) = witness(bench);
+        let proof = bench.prove(params, pk, circuit, &digest);
+        group.bench_with_input(BenchmarkId::from_parameter(k), k, |b, _| {
+            b.iter(|| assert!(bench.verify(params, pk, &proof, &digest)))
+        });
+    }
+    group.finish();
+}
+
+/// Circuit for a fresh random message together with its digest.
+fn witness<B: HashBenchmark>(bench: &B) -> (B::Circuit, B::Digest) {
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    (bench.circuit(message, &digest), digest)
+}
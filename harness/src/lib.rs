@@ -0,0 +1,497 @@
+//! Shared driver for the hash benchmarks in this workspace.
+//!
+//! Each bench crate implements [`HashBenchmark`] for its circuit and hands it to
+//! [`run`], which walks through params generation, keygen, proving and
+//! verification in the same order for every hash and times each phase.
+
+pub mod cache;
+#[cfg(feature = "criterion")]
+pub mod cargo_bench;
+pub mod measure;
+pub mod merkle;
+pub mod report;
+pub mod stats;
+
+use cache::{Contents, Entry};
+use measure::PhaseUsage;
+use stats::PhaseStats;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A hash circuit that can be driven through the halo2 proving pipeline.
+///
+/// The associated types are left abstract because the bench crates pin
+/// different `halo2_proofs` flavours (zcash 0.2, PSE 0.3, the Delphinus fork).
+pub trait HashBenchmark {
+    /// Polynomial commitment parameters.
+    type Params;
+    /// Proving key, which also carries the verifying key.
+    type ProvingKey;
+    /// Preimage fed to the hash.
+    type Message;
+    /// Hash output the circuit is checked against.
+    type Digest;
+    /// Circuit proving that `Digest` is the hash of `Message`.
+    type Circuit;
+
+    /// Name used in the reports, e.g. `poseidon`.
+    fn name(&self) -> String;
+
+    /// Curve the commitment scheme works over, e.g. `vesta`.
+    fn curve(&self) -> &'static str;
+
+    /// Field the circuit is defined over, e.g. `pallas-base`.
+    fn field(&self) -> &'static str;
+
+    /// Polynomial commitment scheme, e.g. `ipa`.
+    fn backend(&self) -> &'static str;
+
+    /// Columns, gates and rows of the circuit when laid out on `2^k` rows.
+    fn circuit_stats(&self, k: u32) -> CircuitStats;
+
+    /// Number of field elements absorbed per hash.
+    fn message_len(&self) -> usize {
+        1
+    }
+
+    /// Number of hashes proven together in one circuit.
+    fn hashes(&self) -> usize {
+        1
+    }
+
+    /// Generates the commitment parameters for `2^k` rows.
+    fn setup(&self, k: u32) -> Self::Params;
+
+    /// Runs `keygen_vk` and `keygen_pk` on the circuit without witnesses.
+    fn keygen(&self, params: &Self::Params) -> Self::ProvingKey;
+
+    /// Samples a random message.
+    fn random_message(&self) -> Self::Message;
+
+    /// Hashes `message` with the native (out-of-circuit) primitive.
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest;
+
+    /// Builds the circuit witnessing `message` and its expected `digest`.
+    fn circuit(&self, message: Self::Message, digest: &Self::Digest) -> Self::Circuit;
+
+    /// Creates a proof for `circuit` and returns the finalized transcript.
+    fn prove(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: Self::Circuit,
+        digest: &Self::Digest,
+    ) -> Vec<u8>;
+
+    /// Checks `proof` against the verifying key held by `pk`.
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        proof: &[u8],
+        digest: &Self::Digest,
+    ) -> bool;
+
+    /// Serialized sizes of `params` and of the keys in `pk`, as far as the
+    /// `halo2_proofs` flavour can serialize them.
+    fn key_sizes(&self, _params: &Self::Params, _pk: &Self::ProvingKey) -> KeySizes {
+        KeySizes::default()
+    }
+
+    /// Identifies the circuit for the [`cache`]; cached params and keys are
+    /// discarded when it changes. Defaults to [`name`](Self::name).
+    fn fingerprint(&self) -> String {
+        self.name()
+    }
+
+    /// Serializes `params`, or fails with [`io::ErrorKind::Unsupported`].
+    fn params_to_bytes(&self, _params: &Self::Params) -> io::Result<Vec<u8>> {
+        Err(cache::unsupported())
+    }
+
+    /// Inverse of [`params_to_bytes`](Self::params_to_bytes).
+    fn params_from_bytes(&self, _bytes: &[u8]) -> io::Result<Self::Params> {
+        Err(cache::unsupported())
+    }
+
+    /// Whether [`pk_to_bytes`](Self::pk_to_bytes) can serialize the proving
+    /// key. The [`cache`] skips keygen when it cannot, rather than generate a
+    /// key only to throw it away.
+    fn supports_pk_cache(&self) -> bool {
+        false
+    }
+
+    /// Serializes `pk`, or fails with [`io::ErrorKind::Unsupported`].
+    fn pk_to_bytes(&self, _pk: &Self::ProvingKey) -> io::Result<Vec<u8>> {
+        Err(cache::unsupported())
+    }
+
+    /// Inverse of [`pk_to_bytes`](Self::pk_to_bytes).
+    fn pk_from_bytes(&self, _params: &Self::Params, _bytes: &[u8]) -> io::Result<Self::ProvingKey> {
+        Err(cache::unsupported())
+    }
+}
+
+/// Serialized sizes in bytes; `None` where they cannot be determined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeySizes {
+    pub params: Option<usize>,
+    pub vk: Option<usize>,
+    pub pk: Option<usize>,
+    /// Size of the VK's commitments alone, for backends that cannot
+    /// serialize the VK; kept apart from the measured [`vk`](Self::vk).
+    pub vk_estimate: Option<usize>,
+}
+
+/// Labels of the benchmark phases used in the reports.
+pub const PHASES: [&str; 4] = ["param", "keygen", "prove", "verify"];
+
+/// Shape of a circuit, read from its `ConstraintSystem` and, where the
+/// `halo2_proofs` flavour has it, `dev::CircuitCost`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    /// Custom gates, each of which may hold several constraints.
+    pub gates: usize,
+    /// Lookup arguments.
+    pub lookups: usize,
+    /// Highest degree over the gates, the permutation and the lookups.
+    pub max_degree: usize,
+    /// Rows the floor planner assigns, not counting blinding rows.
+    pub used_rows: Option<usize>,
+    /// Rows at the end of every advice column that are filled with random
+    /// values to keep the witness zero-knowledge.
+    pub blinding_factors: usize,
+}
+
+impl CircuitStats {
+    /// Total number of advice, fixed and instance columns.
+    pub fn columns(&self) -> usize {
+        self.advice_columns + self.fixed_columns + self.instance_columns
+    }
+
+    /// Smallest `k` whose `2^k` rows hold `rows` usable rows.
+    ///
+    /// halo2 reserves the blinding rows and one more for `l_last`, and needs
+    /// at least three rows besides the blinding ones.
+    pub fn k_for(&self, rows: usize) -> u32 {
+        let rows = (rows + self.blinding_factors + 1).max(self.blinding_factors + 3);
+        rows.next_power_of_two().trailing_zeros()
+    }
+
+    /// Smallest `k` that fits the used rows, if they are known.
+    pub fn min_k(&self) -> Option<u32> {
+        self.used_rows.map(|rows| self.k_for(rows))
+    }
+}
+
+/// `k` at which [`min_k`] lays circuits out. Rows are only counted up to
+/// `2^k`, so this exceeds what any circuit here uses.
+pub const PROBE_K: u32 = 20;
+
+/// Smallest `k` that fits `bench`, or `None` if its used rows are unknown.
+pub fn min_k<B: HashBenchmark>(bench: &B) -> Option<u32> {
+    bench.circuit_stats(PROBE_K).min_k()
+}
+
+/// Largest `n` for which `bench(n)` fits in `2^k` rows.
+///
+/// Batches grow linearly, so `n` is extrapolated from the rows of one and two
+/// hashes and then lowered until the layout fits. Fails if `k` is above
+/// [`PROBE_K`], where the rows are no longer counted, or if not even one hash
+/// fits.
+pub fn max_hashes<B: HashBenchmark>(k: u32, bench: impl Fn(usize) -> B) -> Result<usize, String> {
+    if k > PROBE_K {
+        return Err(format!(
+            "cannot fill k = {}, rows are only counted up to k = {}",
+            k, PROBE_K
+        ));
+    }
+    let stats = |n| bench(n).circuit_stats(PROBE_K);
+    let used_rows = |stats: CircuitStats| {
+        stats
+            .used_rows
+            .expect("batched benchmarks report their used rows")
+    };
+
+    let one = stats(1);
+    let per_hash = used_rows(stats(2)).saturating_sub(used_rows(one)).max(1);
+    let usable = (1 << k) - (one.blinding_factors + 1).min(1 << k);
+    let mut n = match usable.checked_sub(used_rows(one)) {
+        Some(spare) => 1 + spare / per_hash,
+        None => 0,
+    };
+    while n > 0 && stats(n).min_k().is_some_and(|min| min > k) {
+        n -= 1;
+    }
+    match n {
+        0 => Err(format!(
+            "not even one hash of {} fits in 2^{} rows",
+            bench(1).name(),
+            k
+        )),
+        n => Ok(n),
+    }
+}
+
+/// How a benchmark is run.
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    /// log2 of the number of rows, or `None` for the benchmark's [`min_k`].
+    pub k: Option<u32>,
+    /// Measured iterations, at least one.
+    pub iterations: usize,
+    /// Iterations run before measuring and then discarded.
+    pub warmup: usize,
+    /// Directory of the params and key [`cache`], if any.
+    pub cache: Option<PathBuf>,
+}
+
+impl RunConfig {
+    /// A single measured iteration on `2^k` rows without warmup.
+    pub fn new(k: u32) -> Self {
+        Self {
+            k: Some(k),
+            ..Self::fitted()
+        }
+    }
+
+    /// A single measured iteration at the benchmark's [`min_k`].
+    pub fn fitted() -> Self {
+        Self {
+            k: None,
+            iterations: 1,
+            warmup: 0,
+            cache: None,
+        }
+    }
+}
+
+/// Outcome of a benchmark, summarized over all measured iterations.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub hash: String,
+    pub curve: &'static str,
+    pub field: &'static str,
+    pub backend: &'static str,
+    pub k: u32,
+    pub circuit: CircuitStats,
+    pub rows: usize,
+    /// Field elements absorbed per hash.
+    pub message_len: usize,
+    /// Hashes proven in one proof.
+    pub hashes: usize,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub param: PhaseStats,
+    pub keygen: PhaseStats,
+    pub prove: PhaseStats,
+    pub verify: PhaseStats,
+    /// Length of the finalized transcript in bytes.
+    pub proof_size: usize,
+    pub key_sizes: KeySizes,
+    /// Whether the param phase loaded the params from the cache.
+    pub cached_params: bool,
+    /// Whether the keygen phase loaded the proving key from the cache.
+    pub cached_pk: bool,
+    /// Time spent filling the cache, if this run had to.
+    pub cache_write: Option<Duration>,
+}
+
+impl BenchResult {
+    /// Phases in execution order, labelled as in [`PHASES`].
+    pub fn phases(&self) -> [(&'static str, &PhaseStats); 4] {
+        [
+            (PHASES[0], &self.param),
+            (PHASES[1], &self.keygen),
+            (PHASES[2], &self.prove),
+            (PHASES[3], &self.verify),
+        ]
+    }
+
+    /// Mean proving time divided by the number of absorbed field elements.
+    pub fn prove_per_element(&self) -> Duration {
+        self.prove.wall.mean / (self.hashes * self.message_len) as u32
+    }
+
+    /// Mean proving time amortized over the hashes in the proof.
+    pub fn prove_per_hash(&self) -> Duration {
+        self.prove.wall.mean / self.hashes as u32
+    }
+
+    /// Highest peak memory over all phases.
+    pub fn peak_memory_kb(&self) -> Option<u64> {
+        self.phases()
+            .iter()
+            .filter_map(|(_, stats)| stats.peak_memory_kb)
+            .max()
+    }
+}
+
+/// Runs `bench` as configured and summarizes what each phase cost.
+///
+/// Every iteration goes through all phases with a fresh random message. With
+/// a cache, the cache is filled first and the param and keygen phases load
+/// from it instead of generating. Where the flavour cannot serialize the
+/// proving key, only the params are cached and keygen runs in every
+/// iteration, with a notice on stderr.
+///
+/// Fails if the circuit does not fit in `2^k` rows, `k` is left to the
+/// benchmark but its used rows are unknown, or the cache cannot be filled.
+/// Panics if any proof does not verify.
+pub fn run<B: HashBenchmark>(bench: &B, config: &RunConfig) -> Result<BenchResult, String> {
+    assert!(config.iterations > 0, "at least one iteration is required");
+    assert!(bench.hashes() > 0, "at least one hash is required");
+    let k = fit_k(bench, config.k)?;
+
+    let cache = match &config.cache {
+        Some(dir) => {
+            let entry = Entry::new(dir, bench, k);
+            let contents = entry
+                .populate(bench, k)
+                .map_err(|err| format!("cannot fill the cache in {}: {}", dir.display(), err))?;
+            if !bench.supports_pk_cache() {
+                eprintln!(
+                    "Note: {} on {} cannot serialize its proving key; keygen is not cached",
+                    bench.name(),
+                    bench.backend()
+                );
+            }
+            Some((entry, contents))
+        }
+        None => None,
+    };
+
+    for _ in 0..config.warmup {
+        run_once(bench, k, cache.as_ref());
+    }
+    let samples = (0..config.iterations)
+        .map(|_| run_once(bench, k, cache.as_ref()))
+        .collect::<Vec<_>>();
+    let contents = cache
+        .as_ref()
+        .map(|(_, contents)| *contents)
+        .unwrap_or_default();
+    let phase = |usage: fn(&Sample) -> PhaseUsage| {
+        PhaseStats::from_usages(&samples.iter().map(usage).collect::<Vec<_>>())
+    };
+
+    Ok(BenchResult {
+        hash: bench.name(),
+        curve: bench.curve(),
+        field: bench.field(),
+        backend: bench.backend(),
+        k,
+        circuit: bench.circuit_stats(k),
+        rows: 1 << k,
+        message_len: bench.message_len(),
+        hashes: bench.hashes(),
+        iterations: config.iterations,
+        warmup: config.warmup,
+        param: phase(|sample| sample.param),
+        keygen: phase(|sample| sample.keygen),
+        prove: phase(|sample| sample.prove),
+        verify: phase(|sample| sample.verify),
+        proof_size: samples[0].proof_size,
+        key_sizes: samples[0].key_sizes,
+        cached_params: contents.params,
+        cached_pk: contents.pk,
+        cache_write: contents.write,
+    })
+}
+
+/// `k` if given and it fits `bench`, otherwise the smallest `k` that does.
+fn fit_k<B: HashBenchmark>(bench: &B, k: Option<u32>) -> Result<u32, String> {
+    match (k, min_k(bench)) {
+        (Some(k), Some(min)) if k < min => Err(format!(
+            "{} needs k >= {}, got k = {}",
+            bench.name(),
+            min,
+            k
+        )),
+        (Some(k), _) => Ok(k),
+        (None, Some(min)) => Ok(min),
+        (None, None) => Err(format!(
+            "the used rows of {} are unknown, pass k explicitly",
+            bench.name()
+        )),
+    }
+}
+
+/// Usage of every phase in one iteration.
+struct Sample {
+    param: PhaseUsage,
+    keygen: PhaseUsage,
+    prove: PhaseUsage,
+    verify: PhaseUsage,
+    proof_size: usize,
+    key_sizes: KeySizes,
+}
+
+fn run_once<B: HashBenchmark>(bench: &B, k: u32, cache: Option<&(Entry, Contents)>) -> Sample {
+    let (params, param) = match cache {
+        Some((entry, contents)) if contents.params => timed("load param", || {
+            entry
+                .load_params(bench)
+                .expect("cached params should deserialize")
+        }),
+        _ => timed("get param", || bench.setup(k)),
+    };
+    let (pk, keygen) = match cache {
+        Some((entry, contents)) if contents.pk => timed("load pk vk", || {
+            entry
+                .load_pk(bench, &params)
+                .expect("cached proving key should deserialize")
+        }),
+        _ => timed("get pk vk", || bench.keygen(&params)),
+    };
+
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    let circuit = bench.circuit(message, &digest);
+
+    let (proof, prove) = timed("create proof", || {
+        bench.prove(&params, &pk, circuit, &digest)
+    });
+    let (verified, verify) = timed("verify", || bench.verify(&params, &pk, &proof, &digest));
+    assert!(verified, "proof verification failed for {}", bench.name());
+
+    Sample {
+        param,
+        keygen,
+        prove,
+        verify,
+        proof_size: proof.len(),
+        key_sizes: bench.key_sizes(&params, &pk),
+    }
+}
+
+/// Reads `name: <count>` out of a `Debug` dump.
+///
+/// Some `halo2_proofs` versions keep the column counts of `ConstraintSystem`
+/// crate-private; its derived `Debug` output is the only place they show up.
+pub fn debug_count(debug: &str, name: &str) -> usize {
+    let pattern = format!("{}: ", name);
+    let start = debug
+        .find(&pattern)
+        .unwrap_or_else(|| panic!("`{}` not found in debug output", name))
+        + pattern.len();
+    debug[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or_else(|| panic!("`{}` is not followed by a count", name))
+}
+
+/// Runs `f` and returns its resource usage, logging the phase to stderr.
+///
+/// Results go to stdout in a machine-readable format, so progress must not.
+fn timed<T>(label: &str, f: impl FnOnce() -> T) -> (T, PhaseUsage) {
+    eprintln!("Start: {}", label);
+    let (value, usage) = measure::measure(f);
+    eprintln!("End:   {} {:?}", label, usage.wall);
+    (value, usage)
+}
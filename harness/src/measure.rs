@@ -0,0 +1,86 @@
+//! Resource usage of a benchmark phase, sampled from inside the process.
+//!
+//! Peak memory is attributed to a single phase by resetting the kernel's
+//! high-water mark (`/proc/self/clear_refs`) before the phase and reading
+//! `VmHWM` after it, so it is only available on Linux. CPU time comes from
+//! `getrusage` and covers every thread of the process.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// What one phase cost.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseUsage {
+    /// Wall-clock time.
+    pub wall: Duration,
+    /// User plus system CPU time summed over all threads.
+    pub cpu: Option<Duration>,
+    /// Peak resident set size reached during the phase.
+    pub peak_memory_kb: Option<u64>,
+}
+
+impl PhaseUsage {
+    /// CPU time relative to wall-clock time, in percent (`gtime`'s `%P`).
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let wall = self.wall.as_secs_f64();
+        self.cpu
+            .filter(|_| wall > 0.0)
+            .map(|cpu| cpu.as_secs_f64() / wall * 100.0)
+    }
+}
+
+/// Runs `f` and records its wall-clock time, CPU time and peak memory.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, PhaseUsage) {
+    let hwm_reset = reset_peak_memory();
+    let cpu_start = cpu_time();
+    let start = Instant::now();
+
+    let value = f();
+
+    let wall = start.elapsed();
+    let cpu = cpu_start
+        .zip(cpu_time())
+        .map(|(start, end)| end.saturating_sub(start));
+    let peak_memory_kb = if hwm_reset { peak_memory_kb() } else { None };
+
+    (
+        value,
+        PhaseUsage {
+            wall,
+            cpu,
+            peak_memory_kb,
+        },
+    )
+}
+
+/// Resets `VmHWM` to the current resident set size.
+fn reset_peak_memory() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Reads the peak resident set size (`VmHWM`) from `/proc/self/status`.
+fn peak_memory_kb() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(unix)]
+fn cpu_time() -> Option<Duration> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: `getrusage` only writes into the buffer we hand it.
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: the call above succeeded, so the struct is initialised.
+    let usage = unsafe { usage.assume_init() };
+    let timeval = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+    Some(timeval(usage.ru_utime) + timeval(usage.ru_stime))
+}
+
+#[cfg(not(unix))]
+fn cpu_time() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,140 @@
+//! Native binary Merkle trees, used to witness the Merkle path circuits.
+//!
+//! Trees are sparse: every leaf that was never inserted holds the same empty
+//! value, so only the nodes above inserted leaves are stored. A tree of depth
+//! 32 with a handful of leaves costs a few hundred hashes to build.
+
+use std::collections::HashMap;
+
+/// Depths the Merkle path benchmarks are run at.
+pub const DEPTHS: [usize; 3] = [16, 20, 32];
+
+/// Leaves inserted into the random trees the benchmarks prove membership in.
+pub const LEAVES: usize = 16;
+
+/// A fixed-depth binary Merkle tree with the two-to-one hash `compress`.
+#[derive(Debug, Clone)]
+pub struct MerkleTree<T> {
+    depth: usize,
+    compress: fn(&T, &T) -> T,
+    /// `empty[h]` is the root of a subtree of height `h` with no leaves.
+    empty: Vec<T>,
+    /// Nodes above inserted leaves, by height and index within that height.
+    nodes: HashMap<(usize, u64), T>,
+}
+
+impl<T: Clone> MerkleTree<T> {
+    /// A tree of `depth` whose leaves all hold `empty_leaf`.
+    pub fn new(depth: usize, empty_leaf: T, compress: fn(&T, &T) -> T) -> Self {
+        assert!(depth < 64, "leaf indices are u64, got depth {}", depth);
+        let mut empty = vec![empty_leaf];
+        for height in 0..depth {
+            let node = compress(&empty[height], &empty[height]);
+            empty.push(node);
+        }
+        Self {
+            depth,
+            compress,
+            empty,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// The path to one of [`LEAVES`] leaves drawn from `leaf`, inserted at
+    /// positions taken from the low bits of `index`.
+    pub fn random_path(
+        depth: usize,
+        empty_leaf: T,
+        compress: fn(&T, &T) -> T,
+        mut leaf: impl FnMut() -> T,
+        mut index: impl FnMut() -> u64,
+    ) -> MerklePath<T> {
+        let mut tree = Self::new(depth, empty_leaf, compress);
+        let mask = (1 << depth) - 1;
+        let indices: Vec<u64> = (0..LEAVES).map(|_| index() & mask).collect();
+        for &i in &indices {
+            tree.insert(i, leaf());
+        }
+        tree.path(indices[0])
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn root(&self) -> T {
+        self.node(self.depth, 0)
+    }
+
+    /// Sets leaf `index` and rehashes the path above it.
+    pub fn insert(&mut self, index: u64, leaf: T) {
+        assert!(
+            index >> self.depth == 0,
+            "leaf {} is out of range for depth {}",
+            index,
+            self.depth
+        );
+        self.nodes.insert((0, index), leaf);
+        let mut index = index;
+        for height in 0..self.depth {
+            let left = self.node(height, index & !1);
+            let right = self.node(height, index | 1);
+            index >>= 1;
+            self.nodes
+                .insert((height + 1, index), (self.compress)(&left, &right));
+        }
+    }
+
+    /// The inclusion proof of leaf `index`.
+    pub fn path(&self, index: u64) -> MerklePath<T> {
+        MerklePath {
+            leaf: self.node(0, index),
+            index,
+            siblings: (0..self.depth)
+                .map(|height| self.node(height, (index >> height) ^ 1))
+                .collect(),
+        }
+    }
+
+    fn node(&self, height: usize, index: u64) -> T {
+        self.nodes
+            .get(&(height, index))
+            .unwrap_or(&self.empty[height])
+            .clone()
+    }
+}
+
+/// A leaf together with the siblings on its way up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath<T> {
+    pub leaf: T,
+    /// Bit `h` is set when the node at height `h` is a right child.
+    pub index: u64,
+    /// Siblings from the leaf's level up to just below the root.
+    pub siblings: Vec<T>,
+}
+
+impl<T: Clone> MerklePath<T> {
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+
+    /// Whether the node at `height` on this path is a right child.
+    pub fn is_right(&self, height: usize) -> bool {
+        (self.index >> height) & 1 == 1
+    }
+
+    /// Hashes the leaf up to the root it commits to.
+    pub fn root(&self, compress: fn(&T, &T) -> T) -> T {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(self.leaf.clone(), |node, (height, sibling)| {
+                if self.is_right(height) {
+                    compress(sibling, &node)
+                } else {
+                    compress(&node, sibling)
+                }
+            })
+    }
+}
@@ -0,0 +1,159 @@
+//! Machine-readable renderings of [`BenchResult`].
+//!
+//! JSON and CSV share one flat record so the two formats always carry the same
+//! columns. Durations are reported in milliseconds, memory in kilobytes and
+//! serialized sizes in bytes. CSV strings are quoted as in RFC 4180.
+
+use crate::stats::PhaseStats;
+use crate::{BenchResult, PHASES};
+use serde_json::{Map, Value};
+use std::time::Duration;
+
+const LEADING_COLUMNS: [&str; 19] = [
+    "hash",
+    "curve",
+    "field",
+    "backend",
+    "k",
+    "columns",
+    "advice_columns",
+    "fixed_columns",
+    "instance_columns",
+    "gates",
+    "lookups",
+    "max_degree",
+    "rows",
+    "used_rows",
+    "blinding_factors",
+    "message_len",
+    "hashes",
+    "iterations",
+    "warmup",
+];
+
+/// Suffixes of the columns reported for every phase; `<phase>_ms` is the mean.
+const PHASE_COLUMNS: [&str; 9] = [
+    "ms",
+    "median_ms",
+    "stddev_ms",
+    "min_ms",
+    "max_ms",
+    "ci95_low_ms",
+    "ci95_high_ms",
+    "cpu_ms",
+    "peak_kb",
+];
+
+const TRAILING_COLUMNS: [&str; 11] = [
+    "proof_bytes",
+    "params_bytes",
+    "vk_bytes",
+    "vk_estimate_bytes",
+    "pk_bytes",
+    "cached_params",
+    "cached_pk",
+    "cache_write_ms",
+    "peak_memory_kb",
+    "prove_ms_per_element",
+    "prove_ms_per_hash",
+];
+
+/// Column names matching [`csv_row`].
+pub fn csv_header() -> String {
+    column_names().join(",")
+}
+
+/// Renders `result` as a CSV row in the order of [`csv_header`].
+pub fn csv_row(result: &BenchResult) -> String {
+    values(result)
+        .iter()
+        .map(|value| match value {
+            Value::Null => String::new(),
+            Value::String(value) => format!("\"{}\"", value.replace('"', "\"\"")),
+            value => value.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Renders `result` as a single JSON object without a trailing newline.
+pub fn json_line(result: &BenchResult) -> String {
+    let record = column_names()
+        .into_iter()
+        .zip(values(result))
+        .collect::<Map<_, _>>();
+    Value::Object(record).to_string()
+}
+
+fn column_names() -> Vec<String> {
+    let phases = PHASES.iter().flat_map(|phase| {
+        PHASE_COLUMNS
+            .iter()
+            .map(move |suffix| format!("{}_{}", phase, suffix))
+    });
+    LEADING_COLUMNS
+        .iter()
+        .map(|name| name.to_string())
+        .chain(phases)
+        .chain(TRAILING_COLUMNS.iter().map(|name| name.to_string()))
+        .collect()
+}
+
+fn values(result: &BenchResult) -> Vec<Value> {
+    let mut values = vec![
+        result.hash.clone().into(),
+        result.curve.into(),
+        result.field.into(),
+        result.backend.into(),
+        result.k.into(),
+        result.circuit.columns().into(),
+        result.circuit.advice_columns.into(),
+        result.circuit.fixed_columns.into(),
+        result.circuit.instance_columns.into(),
+        result.circuit.gates.into(),
+        result.circuit.lookups.into(),
+        result.circuit.max_degree.into(),
+        result.rows.into(),
+        result.circuit.used_rows.into(),
+        result.circuit.blinding_factors.into(),
+        result.message_len.into(),
+        result.hashes.into(),
+        result.iterations.into(),
+        result.warmup.into(),
+    ];
+    for (_, stats) in result.phases() {
+        values.extend(phase_values(stats));
+    }
+    values.push(result.proof_size.into());
+    values.push(result.key_sizes.params.into());
+    values.push(result.key_sizes.vk.into());
+    values.push(result.key_sizes.vk_estimate.into());
+    values.push(result.key_sizes.pk.into());
+    values.push(result.cached_params.into());
+    values.push(result.cached_pk.into());
+    values.push(result.cache_write.map(millis).into());
+    values.push(result.peak_memory_kb().into());
+    values.push(millis(result.prove_per_element()));
+    values.push(millis(result.prove_per_hash()));
+    values
+}
+
+fn phase_values(stats: &PhaseStats) -> [Value; 9] {
+    let wall = &stats.wall;
+    [
+        millis(wall.mean),
+        millis(wall.median),
+        millis(wall.stddev),
+        millis(wall.min),
+        millis(wall.max),
+        millis(wall.ci95.0),
+        millis(wall.ci95.1),
+        stats.cpu.map(|cpu| millis(cpu.mean)).into(),
+        stats.peak_memory_kb.into(),
+    ]
+}
+
+/// Milliseconds rounded to microsecond precision.
+fn millis(duration: Duration) -> Value {
+    ((duration.as_secs_f64() * 1e6).round() / 1e3).into()
+}
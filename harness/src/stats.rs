@@ -0,0 +1,111 @@
+//! Summary statistics over repeated benchmark runs.
+
+use crate::measure::PhaseUsage;
+use std::time::Duration;
+
+/// Distribution of a duration over all measured iterations.
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub mean: Duration,
+    pub median: Duration,
+    /// Sample standard deviation; zero for a single sample.
+    pub stddev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Two-sided 95% confidence interval of the mean (Student's t).
+    pub ci95: (Duration, Duration),
+}
+
+impl Summary {
+    /// Summarizes `samples`, which must not be empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "cannot summarize zero samples");
+
+        let mut secs = samples
+            .iter()
+            .map(Duration::as_secs_f64)
+            .collect::<Vec<_>>();
+        secs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let n = secs.len();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let median = if n % 2 == 0 {
+            (secs[n / 2 - 1] + secs[n / 2]) / 2.0
+        } else {
+            secs[n / 2]
+        };
+        let variance = if n > 1 {
+            secs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        let stddev = variance.sqrt();
+        let half_width = if n > 1 {
+            t_975(n - 1) * stddev / (n as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        Self {
+            mean: Duration::from_secs_f64(mean),
+            median: Duration::from_secs_f64(median),
+            stddev: Duration::from_secs_f64(stddev),
+            min: Duration::from_secs_f64(secs[0]),
+            max: Duration::from_secs_f64(secs[n - 1]),
+            ci95: (
+                Duration::from_secs_f64((mean - half_width).max(0.0)),
+                Duration::from_secs_f64(mean + half_width),
+            ),
+        }
+    }
+}
+
+/// Per-phase statistics over all measured iterations.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseStats {
+    pub wall: Summary,
+    pub cpu: Option<Summary>,
+    /// Highest peak memory seen in any iteration.
+    pub peak_memory_kb: Option<u64>,
+}
+
+impl PhaseStats {
+    /// Summarizes the usage of one phase across iterations.
+    pub fn from_usages(usages: &[PhaseUsage]) -> Self {
+        let wall = usages.iter().map(|usage| usage.wall).collect::<Vec<_>>();
+        let cpu = usages
+            .iter()
+            .map(|usage| usage.cpu)
+            .collect::<Option<Vec<_>>>();
+
+        Self {
+            wall: Summary::from_samples(&wall),
+            cpu: cpu.map(|cpu| Summary::from_samples(&cpu)),
+            peak_memory_kb: usages.iter().filter_map(|usage| usage.peak_memory_kb).max(),
+        }
+    }
+
+    /// Mean CPU time relative to mean wall-clock time, in percent.
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let wall = self.wall.mean.as_secs_f64();
+        self.cpu
+            .filter(|_| wall > 0.0)
+            .map(|cpu| cpu.mean.as_secs_f64() / wall * 100.0)
+    }
+}
+
+/// 97.5% quantile of Student's t distribution with `df` degrees of freedom.
+fn t_975(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
@@ -0,0 +1,67 @@
+//! CSV rows must stay parseable whatever the strings in them hold.
+
+use halo2_bench_harness::measure::PhaseUsage;
+use halo2_bench_harness::report::{csv_header, csv_row};
+use halo2_bench_harness::stats::PhaseStats;
+use halo2_bench_harness::{BenchResult, CircuitStats, KeySizes};
+use std::time::Duration;
+
+fn result(hash: &str) -> BenchResult {
+    let phase = PhaseStats::from_usages(&[PhaseUsage {
+        wall: Duration::from_millis(3),
+        ..PhaseUsage::default()
+    }]);
+    BenchResult {
+        hash: hash.to_string(),
+        curve: "vesta",
+        field: "pallas-base",
+        backend: "ipa",
+        k: 4,
+        circuit: CircuitStats::default(),
+        rows: 16,
+        message_len: 2,
+        hashes: 1,
+        iterations: 1,
+        warmup: 0,
+        param: phase,
+        keygen: phase,
+        prove: phase,
+        verify: phase,
+        proof_size: 100,
+        key_sizes: KeySizes::default(),
+        cached_params: false,
+        cached_pk: false,
+        cache_write: None,
+    }
+}
+
+/// Splits an RFC 4180 row into its fields.
+fn fields(row: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    assert!(!quoted, "unterminated quote in {}", row);
+    fields
+}
+
+#[test]
+fn quotes_strings_with_commas_and_quotes() {
+    let hash = "poseidon, \"wide\"\nstate";
+    let row = csv_row(&result(hash));
+    let fields = fields(&row);
+    assert_eq!(fields.len(), csv_header().split(',').count());
+    assert_eq!(fields[0], hash);
+    assert_eq!(fields[1..4], ["vesta", "pallas-base", "ipa"]);
+    assert_eq!(fields[4], "4");
+}
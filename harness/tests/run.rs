@@ -0,0 +1,126 @@
+//! `run` must fail cleanly on a `k` that cannot work, fit the circuit
+//! otherwise, and not generate keys the cache cannot hold.
+
+use halo2_bench_harness::{run, CircuitStats, HashBenchmark, RunConfig};
+use std::cell::Cell;
+use std::io;
+
+/// Benchmark whose "proof" is the message itself, laid out on `rows` rows if
+/// they are known. Its params serialize, its proving key does not.
+#[derive(Default)]
+struct Echo {
+    rows: Option<usize>,
+    keygens: Cell<usize>,
+}
+
+impl Echo {
+    fn new(rows: Option<usize>) -> Self {
+        Self {
+            rows,
+            ..Self::default()
+        }
+    }
+}
+
+impl HashBenchmark for Echo {
+    type Params = ();
+    type ProvingKey = ();
+    type Message = u64;
+    type Digest = u64;
+    type Circuit = u64;
+
+    fn name(&self) -> String {
+        "echo".to_string()
+    }
+
+    fn curve(&self) -> &'static str {
+        "none"
+    }
+
+    fn field(&self) -> &'static str {
+        "u64"
+    }
+
+    fn backend(&self) -> &'static str {
+        "echo"
+    }
+
+    fn circuit_stats(&self, _k: u32) -> CircuitStats {
+        CircuitStats {
+            used_rows: self.rows,
+            blinding_factors: 5,
+            ..CircuitStats::default()
+        }
+    }
+
+    fn setup(&self, _k: u32) {}
+
+    fn keygen(&self, _params: &()) {
+        self.keygens.set(self.keygens.get() + 1);
+    }
+
+    fn random_message(&self) -> u64 {
+        42
+    }
+
+    fn native_hash(&self, message: &u64) -> u64 {
+        *message
+    }
+
+    fn circuit(&self, message: u64, _digest: &u64) -> u64 {
+        message
+    }
+
+    fn prove(&self, _params: &(), _pk: &(), circuit: u64, _digest: &u64) -> Vec<u8> {
+        circuit.to_le_bytes().to_vec()
+    }
+
+    fn verify(&self, _params: &(), _pk: &(), proof: &[u8], digest: &u64) -> bool {
+        proof == digest.to_le_bytes()
+    }
+
+    fn params_to_bytes(&self, _params: &()) -> io::Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn params_from_bytes(&self, _bytes: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn fits_k_to_the_used_rows() {
+    let bench = Echo::new(Some(100));
+    assert_eq!(run(&bench, &RunConfig::fitted()).unwrap().k, 7);
+    assert_eq!(run(&bench, &RunConfig::new(9)).unwrap().k, 9);
+}
+
+#[test]
+fn rejects_k_below_the_used_rows() {
+    let err = run(&Echo::new(Some(100)), &RunConfig::new(5)).unwrap_err();
+    assert_eq!(err, "echo needs k >= 7, got k = 5");
+}
+
+#[test]
+fn needs_k_when_the_rows_are_unknown() {
+    assert!(run(&Echo::new(None), &RunConfig::fitted()).is_err());
+    assert_eq!(run(&Echo::new(None), &RunConfig::new(4)).unwrap().k, 4);
+}
+
+#[test]
+fn caches_params_without_keygen_when_the_key_cannot_be() {
+    let dir = std::env::temp_dir().join(format!("halo2-bench-cache-{}", std::process::id()));
+    let config = RunConfig {
+        cache: Some(dir.clone()),
+        iterations: 2,
+        ..RunConfig::new(4)
+    };
+    for _ in 0..2 {
+        let bench = Echo::new(None);
+        let result = run(&bench, &config).unwrap();
+        assert!(result.cached_params);
+        assert!(!result.cached_pk);
+        assert_eq!(bench.keygens.get(), config.iterations);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_bench_harness::cargo_bench::prove_and_verify;
+use halo2_bench_harness::merkle::DEPTHS;
+use halo2_bench_harness::{max_hashes, min_k};
+use halo2_bench_mimc::field::{MiMCField, Pallas, Vesta};
+use halo2_bench_mimc::merkle::MiMC5MerkleBenchmark;
+use halo2_bench_mimc::MiMC5Benchmark;
+
+const BATCH_K: u32 = 12;
+
+/// A single hash, a batch of as many hashes as fit in `2^BATCH_K` rows, and
+/// Merkle paths of each depth in [`DEPTHS`] at their smallest `k`.
+fn mimc<M: MiMCField>(c: &mut Criterion, field: M) {
+    prove_and_verify(c, &MiMC5Benchmark::new(field), &[7, 8, 9]);
+
+    let n = max_hashes(BATCH_K, |n| MiMC5Benchmark::batch(field, n))
+        .expect("a hash fits in 2^BATCH_K rows");
+    prove_and_verify(c, &MiMC5Benchmark::batch(field, n), &[BATCH_K]);
+
+    for depth in DEPTHS {
+        let bench = MiMC5MerkleBenchmark::new(field, depth);
+        let k = min_k(&bench).expect("the Merkle path reports its used rows");
+        prove_and_verify(c, &bench, &[k]);
+    }
+}
+
+fn mimc_pallas(c: &mut Criterion) {
+    mimc(c, Pallas);
+}
+
+fn mimc_vesta(c: &mut Criterion) {
+    mimc(c, Vesta);
+}
+
+criterion_group!(benches, mimc_pallas, mimc_vesta);
+criterion_main!(benches);
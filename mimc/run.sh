@@ -0,0 +1,3 @@
+#!/bin/bash
+
+RUSTFLAGS=-Ctarget-cpu=native cargo run --release --package halo2_bench_mimc --bin halo2_bench_mimc
\ No newline at end of file
@@ -0,0 +1,142 @@
+//! Fields MiMC5 is benchmarked over.
+//!
+//! `mimc_halo2` has a chip and a native hash per field. [`MiMCField`] picks
+//! them together with the curve the circuit is committed on, so that the
+//! circuits and benchmarks in this crate are written once over both Pasta
+//! fields.
+//!
+//! Only the Pasta fields can be implemented. `mimc_halo2` has no chip for
+//! any other field, and it is built on zcash `halo2_proofs` 0.2, whose only
+//! commitment scheme is IPA over the Pasta cycle. BN254 or the BLS12-381
+//! scalar field would need the chip ported to a `halo2_proofs` with KZG, as
+//! the Poseidon crate is, rather than another implementation here.
+
+use halo2_proofs::{
+    arithmetic::{CurveAffine, FieldExt},
+    circuit::{AssignedCell, Layouter},
+    pasta::{Fp, Fq},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+use mimc_halo2::mimc::{
+    mimc_hash::{MiMC5HashChip, MiMC5HashConfig, MiMC5HashPallasChip, MiMC5HashVestaChip},
+    primitives::{mimc5_hash_pallas, mimc5_hash_vesta},
+};
+use pasta_curves::{pallas, vesta};
+use std::fmt::Debug;
+
+/// A Pasta field with its MiMC5 chip, and the other curve of the cycle that
+/// circuits over it are proven on.
+pub trait MiMCField: Debug + Clone + Copy + Default {
+    /// Field the hash and the circuit are defined over.
+    type Field: FieldExt;
+    /// Curve whose scalar field is [`Self::Field`], committed to with IPA.
+    type Curve: CurveAffine<ScalarExt = Self::Field>;
+
+    /// Field as it appears in benchmark names, e.g. `pallas`.
+    fn name(&self) -> &'static str;
+
+    /// Curve the commitments live on, e.g. `vesta`.
+    fn curve(&self) -> &'static str;
+
+    /// Field the circuit is defined over, e.g. `pallas-base`.
+    fn field(&self) -> &'static str;
+
+    /// Hashes `state` natively, in place.
+    fn hash(state: &mut Self::Field);
+
+    /// Configures the hash chip on `state` and `round_constants`.
+    fn configure(
+        meta: &mut ConstraintSystem<Self::Field>,
+        state: Column<Advice>,
+        round_constants: Column<Fixed>,
+    ) -> MiMC5HashConfig;
+
+    /// Hashes `message` with the chip configured as `config`.
+    fn hash_message(
+        config: &MiMC5HashConfig,
+        layouter: impl Layouter<Self::Field>,
+        message: &AssignedCell<Self::Field, Self::Field>,
+    ) -> Result<AssignedCell<Self::Field, Self::Field>, Error>;
+}
+
+/// The Pallas base field, proven with IPA on Vesta.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pallas;
+
+impl MiMCField for Pallas {
+    type Field = Fp;
+    type Curve = vesta::Affine;
+
+    fn name(&self) -> &'static str {
+        "pallas"
+    }
+
+    fn curve(&self) -> &'static str {
+        "vesta"
+    }
+
+    fn field(&self) -> &'static str {
+        "pallas-base"
+    }
+
+    fn hash(state: &mut Fp) {
+        mimc5_hash_pallas(state)
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        state: Column<Advice>,
+        round_constants: Column<Fixed>,
+    ) -> MiMC5HashConfig {
+        MiMC5HashPallasChip::configure(meta, state, round_constants)
+    }
+
+    fn hash_message(
+        config: &MiMC5HashConfig,
+        layouter: impl Layouter<Fp>,
+        message: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        MiMC5HashPallasChip::construct(config.clone()).hash_message(layouter, message)
+    }
+}
+
+/// The Vesta base field, proven with IPA on Pallas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vesta;
+
+impl MiMCField for Vesta {
+    type Field = Fq;
+    type Curve = pallas::Affine;
+
+    fn name(&self) -> &'static str {
+        "vesta"
+    }
+
+    fn curve(&self) -> &'static str {
+        "pallas"
+    }
+
+    fn field(&self) -> &'static str {
+        "vesta-base"
+    }
+
+    fn hash(state: &mut Fq) {
+        mimc5_hash_vesta(state)
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<Fq>,
+        state: Column<Advice>,
+        round_constants: Column<Fixed>,
+    ) -> MiMC5HashConfig {
+        MiMC5HashVestaChip::configure(meta, state, round_constants)
+    }
+
+    fn hash_message(
+        config: &MiMC5HashConfig,
+        layouter: impl Layouter<Fq>,
+        message: &AssignedCell<Fq, Fq>,
+    ) -> Result<AssignedCell<Fq, Fq>, Error> {
+        MiMC5HashVestaChip::construct(config.clone()).hash_message(layouter, message)
+    }
+}
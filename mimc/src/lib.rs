@@ -0,0 +1,316 @@
+use halo2_bench_harness::{CircuitStats, HashBenchmark, KeySizes};
+use mimc_halo2::mimc::mimc_hash::MiMC5HashConfig;
+use rand::rngs::OsRng;
+use std::io;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
+        ConstraintSystem, Error, Instance, ProvingKey, SingleVerifier,
+    },
+    poly::commitment::Params,
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+
+pub mod field;
+pub mod merkle;
+mod rows;
+
+use field::MiMCField;
+
+#[derive(Debug, Clone)]
+pub struct MiMC5HashCircuitConfig {
+    input: Column<Advice>,
+    /// Holds the digests when they are public.
+    expected: Option<Column<Instance>>,
+    mimc_config: MiMC5HashConfig,
+}
+
+/// Proves that each of `message_hashes` is the MiMC5 hash of the message at
+/// the same index.
+///
+/// With `PUBLIC`, hash `i` is constrained to row `i` of the instance column,
+/// as in the Poseidon benchmarks, and `message_hashes` goes unused. Otherwise
+/// the digests are witnessed in the advice column and the verifier learns
+/// nothing about them.
+#[derive(Default, Clone)]
+pub struct MiMC5HashCircuit<M: MiMCField, const PUBLIC: bool> {
+    pub messages: Vec<M::Field>,
+    pub message_hashes: Vec<M::Field>,
+}
+
+impl<M: MiMCField, const PUBLIC: bool> Circuit<M::Field> for MiMC5HashCircuit<M, PUBLIC> {
+    type Config = MiMC5HashCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            messages: vec![M::Field::zero(); self.messages.len()],
+            message_hashes: vec![M::Field::zero(); self.message_hashes.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<M::Field>) -> Self::Config {
+        let circuit_input = meta.advice_column();
+        meta.enable_equality(circuit_input);
+        let expected = PUBLIC.then(|| {
+            let expected = meta.instance_column();
+            meta.enable_equality(expected);
+            expected
+        });
+        let state = meta.advice_column();
+        let round_constants = meta.fixed_column();
+
+        Self::Config {
+            input: circuit_input,
+            expected,
+            mimc_config: M::configure(meta, state, round_constants),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<M::Field>,
+    ) -> Result<(), Error> {
+        for (i, (message, message_hash)) in
+            self.messages.iter().zip(&self.message_hashes).enumerate()
+        {
+            let message = layouter.assign_region(
+                || format!("load message {}", i),
+                |mut region| {
+                    region.assign_advice(
+                        || "load input message",
+                        config.input,
+                        0,
+                        || Value::known(*message),
+                    )
+                },
+            )?;
+
+            let msg_hash = M::hash_message(
+                &config.mimc_config,
+                layouter.namespace(|| format!("hash message {}", i)),
+                &message,
+            )?;
+
+            match config.expected {
+                Some(expected) => layouter.constrain_instance(msg_hash.cell(), expected, i)?,
+                None => layouter.assign_region(
+                    || format!("constrain output {}", i),
+                    |mut region| {
+                        let expected_output = region.assign_advice(
+                            || "load expected output",
+                            config.input,
+                            0,
+                            || Value::known(*message_hash),
+                        )?;
+                        region.constrain_equal(msg_hash.cell(), expected_output.cell())
+                    },
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// MiMC5 over the base field of one Pasta curve, proven with IPA on the
+/// other.
+///
+/// Proves a single hash unless built with [`batch`](Self::batch), and exposes
+/// the digests as public inputs unless built with
+/// [`private`](MiMC5Benchmark::private).
+#[derive(Debug, Clone, Copy)]
+pub struct MiMC5Benchmark<M, const PUBLIC: bool = true> {
+    field: M,
+    hashes: usize,
+}
+
+impl<M: MiMCField> MiMC5Benchmark<M> {
+    /// Proves a single hash.
+    pub fn new(field: M) -> Self {
+        Self::batch(field, 1)
+    }
+
+    /// Proves `hashes` independent hashes in one circuit.
+    pub fn batch(field: M, hashes: usize) -> Self {
+        Self { field, hashes }
+    }
+}
+
+impl<M: MiMCField> MiMC5Benchmark<M, false> {
+    /// Proves `hashes` hashes with the digests kept in the advice column.
+    pub fn private(field: M, hashes: usize) -> Self {
+        Self { field, hashes }
+    }
+}
+
+impl<M: MiMCField, const PUBLIC: bool> MiMC5Benchmark<M, PUBLIC> {
+    fn empty_circuit(&self) -> MiMC5HashCircuit<M, PUBLIC> {
+        MiMC5HashCircuit {
+            messages: vec![M::Field::zero(); self.hashes],
+            message_hashes: vec![M::Field::zero(); self.hashes],
+        }
+    }
+}
+
+impl<M: MiMCField, const PUBLIC: bool> HashBenchmark for MiMC5Benchmark<M, PUBLIC> {
+    type Params = Params<M::Curve>;
+    type ProvingKey = ProvingKey<M::Curve>;
+    type Message = Vec<M::Field>;
+    type Digest = Vec<M::Field>;
+    type Circuit = MiMC5HashCircuit<M, PUBLIC>;
+
+    fn name(&self) -> String {
+        let name = if PUBLIC {
+            format!("mimc5-{}", self.field.name())
+        } else {
+            format!("mimc5-{}-private", self.field.name())
+        };
+        match self.hashes {
+            1 => name,
+            n => format!("{}-n{}", name, n),
+        }
+    }
+
+    fn curve(&self) -> &'static str {
+        self.field.curve()
+    }
+
+    fn field(&self) -> &'static str {
+        self.field.field()
+    }
+
+    fn backend(&self) -> &'static str {
+        "ipa"
+    }
+
+    fn circuit_stats(&self, k: u32) -> CircuitStats {
+        circuit_stats::<M, _>(k, &self.empty_circuit())
+    }
+
+    fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    fn setup(&self, k: u32) -> Self::Params {
+        // Initialize the polynomial commitment parameters
+        Params::new(k)
+    }
+
+    fn keygen(&self, params: &Self::Params) -> Self::ProvingKey {
+        let empty_circuit = self.empty_circuit();
+
+        // Initialize the proving key
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    fn random_message(&self) -> Self::Message {
+        (0..self.hashes).map(|_| M::Field::random(OsRng)).collect()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message
+            .iter()
+            .map(|message| {
+                let mut state = *message;
+                M::hash(&mut state);
+                state
+            })
+            .collect()
+    }
+
+    fn circuit(&self, message: Self::Message, digest: &Self::Digest) -> Self::Circuit {
+        MiMC5HashCircuit {
+            messages: message,
+            message_hashes: digest.clone(),
+        }
+    }
+
+    fn prove(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: Self::Circuit,
+        digest: &Self::Digest,
+    ) -> Vec<u8> {
+        let digests = [digest.as_slice()];
+        let instance: &[&[M::Field]] = if PUBLIC { &digests } else { &[] };
+
+        // Create a proof
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(params, pk, &[circuit], &[instance], OsRng, &mut transcript)
+            .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        proof: &[u8],
+        digest: &Self::Digest,
+    ) -> bool {
+        let digests = [digest.as_slice()];
+        let instance: &[&[M::Field]] = if PUBLIC { &digests } else { &[] };
+
+        // Verify the proof
+        let strategy = SingleVerifier::new(params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof(params, pk.get_vk(), strategy, &[instance], &mut transcript).is_ok()
+    }
+
+    /// `halo2_proofs` 0.2 can only serialize the params, not the keys.
+    fn key_sizes(&self, params: &Self::Params, _pk: &Self::ProvingKey) -> KeySizes {
+        let mut bytes = vec![];
+        params
+            .write(&mut bytes)
+            .expect("writing to a Vec should not fail");
+        KeySizes {
+            params: Some(bytes.len()),
+            ..KeySizes::default()
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        circuit_fingerprint::<M::Field, Self::Circuit>(&self.name())
+    }
+
+    fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![];
+        params.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn params_from_bytes(&self, bytes: &[u8]) -> io::Result<Self::Params> {
+        Params::read(bytes)
+    }
+}
+
+/// Layout of `circuit` on `2^k` rows.
+pub fn circuit_stats<M: MiMCField, C: Circuit<M::Field>>(k: u32, circuit: &C) -> CircuitStats {
+    let mut meta = ConstraintSystem::<M::Field>::default();
+    C::configure(&mut meta);
+    CircuitStats {
+        advice_columns: meta.num_advice_columns(),
+        fixed_columns: meta.num_fixed_columns(),
+        instance_columns: meta.num_instance_columns(),
+        gates: meta.gates().len(),
+        lookups: meta.lookups().len(),
+        max_degree: meta.degree(),
+        used_rows: Some(rows::used_rows(k, circuit)),
+        blinding_factors: meta.blinding_factors(),
+    }
+}
+
+/// `name` together with the gates and column layout of `C`; the round
+/// constants are fixed by `mimc_halo2` itself.
+fn circuit_fingerprint<F: Field, C: Circuit<F>>(name: &str) -> String {
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+    format!("{} {:?}", name, meta)
+}
@@ -0,0 +1,14 @@
+use halo2_bench_harness::RunConfig;
+use halo2_bench_mimc::field::{Pallas, Vesta};
+use halo2_bench_mimc::MiMC5Benchmark;
+
+fn main() -> Result<(), String> {
+    let result = halo2_bench_harness::run(&MiMC5Benchmark::new(Pallas), &RunConfig::fitted())?;
+    println!("{:?}", result);
+
+    let result = halo2_bench_harness::run(&MiMC5Benchmark::new(Vesta), &RunConfig::fitted())?;
+    println!("{:?}", result);
+
+    println!("Proof verification successful for MiMC hash on Pallas and Vesta!");
+    Ok(())
+}
@@ -0,0 +1,372 @@
+//! Merkle path verification with MiMC5, over any [`MiMCField`].
+//!
+//! **This tree is not collision resistant and must not be used for anything
+//! but comparing proving costs.** `mimc_halo2` only provides an unkeyed
+//! permutation of a single field element, so two children are compressed by
+//! chaining it: `mimc5(mimc5(left) + right)`. Anyone can invert that chain:
+//! for any `left'`, the sibling `right' = mimc5(left) + right - mimc5(left')`
+//! gives the same node, so membership of arbitrary leaves can be forged. A
+//! sound construction needs a keyed MiMC (Miyaguchi–Preneel) or a two-element
+//! sponge, neither of which the chip offers; both cost the same two
+//! permutations per level measured here. The benchmark name carries
+//! `insecure` so results are not mistaken for a usable tree, and the CLI
+//! leaves it out of `all`.
+
+use crate::field::MiMCField;
+use crate::{circuit_fingerprint, circuit_stats, MiMC5Benchmark};
+use halo2_bench_harness::merkle::{MerklePath, MerkleTree};
+use halo2_bench_harness::{BenchResult, CircuitStats, HashBenchmark, KeySizes, RunConfig};
+use mimc_halo2::mimc::mimc_hash::MiMC5HashConfig;
+use rand::{rngs::OsRng, RngCore};
+use std::io;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
+        ConstraintSystem, Error, Expression, Instance, ProvingKey, Selector, SingleVerifier,
+    },
+    poly::{commitment::Params, Rotation},
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+
+/// MiMC5 of `mimc5(left) + right`, the compression the tree is built with.
+///
+/// Not collision resistant, see the [module documentation](self).
+pub fn compress<M: MiMCField>(left: &M::Field, right: &M::Field) -> M::Field {
+    let mut state = *left;
+    M::hash(&mut state);
+    state += right;
+    M::hash(&mut state);
+    state
+}
+
+#[derive(Debug, Clone)]
+pub struct MiMC5MerkleConfig {
+    /// Node, sibling and index bit of a level, with the ordered pair on the
+    /// next row; also the two summands and their sum fed to the second hash.
+    advice: [Column<Advice>; 3],
+    swap: Selector,
+    add: Selector,
+    root: Column<Instance>,
+    mimc_config: MiMC5HashConfig,
+}
+
+/// Proves that the instance column holds the root of `path`.
+#[derive(Clone)]
+pub struct MiMC5MerkleCircuit<M: MiMCField> {
+    pub path: MerklePath<M::Field>,
+}
+
+impl<M: MiMCField> Circuit<M::Field> for MiMC5MerkleCircuit<M> {
+    type Config = MiMC5MerkleConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            path: empty_path(self.path.depth()),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<M::Field>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let root = meta.instance_column();
+        meta.enable_equality(root);
+        let state = meta.advice_column();
+        let round_constants = meta.fixed_column();
+
+        let swap = meta.selector();
+        meta.create_gate("swap", |meta| {
+            let swap = meta.query_selector(swap);
+            let node = meta.query_advice(advice[0], Rotation::cur());
+            let sibling = meta.query_advice(advice[1], Rotation::cur());
+            let bit = meta.query_advice(advice[2], Rotation::cur());
+            let left = meta.query_advice(advice[0], Rotation::next());
+            let right = meta.query_advice(advice[1], Rotation::next());
+
+            let one = Expression::Constant(M::Field::one());
+            vec![
+                swap.clone() * bit.clone() * (one - bit.clone()),
+                swap.clone()
+                    * (left.clone() - node.clone() - bit * (sibling.clone() - node.clone())),
+                swap * (left + right - node - sibling),
+            ]
+        });
+
+        let add = meta.selector();
+        meta.create_gate("add", |meta| {
+            let add = meta.query_selector(add);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let sum = meta.query_advice(advice[2], Rotation::cur());
+            vec![add * (a + b - sum)]
+        });
+
+        Self::Config {
+            advice,
+            swap,
+            add,
+            root,
+            mimc_config: M::configure(meta, state, round_constants),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<M::Field>,
+    ) -> Result<(), Error> {
+        let mut node = layouter.assign_region(
+            || "load leaf",
+            |mut region| {
+                region.assign_advice(
+                    || "leaf",
+                    config.advice[0],
+                    0,
+                    || Value::known(self.path.leaf),
+                )
+            },
+        )?;
+
+        for height in 0..self.path.depth() {
+            let [left, right] = layouter.assign_region(
+                || format!("swap {}", height),
+                |mut region| self.swap(&config, &mut region, &node, height),
+            )?;
+
+            let left = M::hash_message(
+                &config.mimc_config,
+                layouter.namespace(|| format!("hash left {}", height)),
+                &left,
+            )?;
+
+            let sum = layouter.assign_region(
+                || format!("add right {}", height),
+                |mut region| {
+                    config.add.enable(&mut region, 0)?;
+                    left.copy_advice(|| "hashed left", &mut region, config.advice[0], 0)?;
+                    right.copy_advice(|| "right", &mut region, config.advice[1], 0)?;
+                    let sum = left.value().zip(right.value()).map(|(a, b)| *a + b);
+                    region.assign_advice(|| "sum", config.advice[2], 0, || sum)
+                },
+            )?;
+
+            node = M::hash_message(
+                &config.mimc_config,
+                layouter.namespace(|| format!("hash sum {}", height)),
+                &sum,
+            )?;
+        }
+
+        layouter.constrain_instance(node.cell(), config.root, 0)
+    }
+}
+
+impl<M: MiMCField> MiMC5MerkleCircuit<M> {
+    /// Assigns `node` and its sibling at `height`, followed by the two in the
+    /// order they are hashed.
+    fn swap(
+        &self,
+        config: &MiMC5MerkleConfig,
+        region: &mut Region<'_, M::Field>,
+        node: &AssignedCell<M::Field, M::Field>,
+        height: usize,
+    ) -> Result<[AssignedCell<M::Field, M::Field>; 2], Error> {
+        config.swap.enable(region, 0)?;
+
+        let sibling = self.path.siblings[height];
+        let is_right = self.path.is_right(height);
+        node.copy_advice(|| "node", region, config.advice[0], 0)?;
+        region.assign_advice(|| "sibling", config.advice[1], 0, || Value::known(sibling))?;
+        region.assign_advice(
+            || "index bit",
+            config.advice[2],
+            0,
+            || Value::known(M::Field::from(is_right as u64)),
+        )?;
+
+        let (left, right) = node
+            .value()
+            .map(|&node| {
+                if is_right {
+                    (sibling, node)
+                } else {
+                    (node, sibling)
+                }
+            })
+            .unzip();
+        let left = region.assign_advice(|| "left", config.advice[0], 1, || left)?;
+        let right = region.assign_advice(|| "right", config.advice[1], 1, || right)?;
+        Ok([left, right])
+    }
+}
+
+/// An all-zero path of `depth`, for keygen.
+fn empty_path<F: Field>(depth: usize) -> MerklePath<F> {
+    MerklePath {
+        leaf: F::zero(),
+        index: 0,
+        siblings: vec![F::zero(); depth],
+    }
+}
+
+/// Membership of a leaf in a MiMC5 Merkle tree of the given depth, proven
+/// with IPA on the other Pasta curve.
+///
+/// Every level is two MiMC5 hashes, so [`hashes`](HashBenchmark::hashes) is
+/// twice the depth. The tree is forgeable (see the [module
+/// documentation](self)); only its cost is meaningful.
+#[derive(Debug, Clone, Copy)]
+pub struct MiMC5MerkleBenchmark<M> {
+    field: M,
+    depth: usize,
+}
+
+impl<M: MiMCField> MiMC5MerkleBenchmark<M> {
+    pub fn new(field: M, depth: usize) -> Self {
+        Self { field, depth }
+    }
+
+    fn empty_circuit(&self) -> MiMC5MerkleCircuit<M> {
+        MiMC5MerkleCircuit {
+            path: empty_path(self.depth),
+        }
+    }
+}
+
+impl<M: MiMCField> HashBenchmark for MiMC5MerkleBenchmark<M> {
+    type Params = Params<M::Curve>;
+    type ProvingKey = ProvingKey<M::Curve>;
+    type Message = MerklePath<M::Field>;
+    type Digest = M::Field;
+    type Circuit = MiMC5MerkleCircuit<M>;
+
+    fn name(&self) -> String {
+        format!(
+            "mimc5-{}-merkle-insecure-d{}",
+            self.field.name(),
+            self.depth
+        )
+    }
+
+    fn curve(&self) -> &'static str {
+        self.field.curve()
+    }
+
+    fn field(&self) -> &'static str {
+        self.field.field()
+    }
+
+    fn backend(&self) -> &'static str {
+        "ipa"
+    }
+
+    fn circuit_stats(&self, k: u32) -> CircuitStats {
+        circuit_stats::<M, _>(k, &self.empty_circuit())
+    }
+
+    fn hashes(&self) -> usize {
+        2 * self.depth
+    }
+
+    fn setup(&self, k: u32) -> Self::Params {
+        Params::new(k)
+    }
+
+    fn keygen(&self, params: &Self::Params) -> Self::ProvingKey {
+        let empty_circuit = self.empty_circuit();
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    fn random_message(&self) -> Self::Message {
+        MerkleTree::random_path(
+            self.depth,
+            M::Field::zero(),
+            compress::<M>,
+            || M::Field::random(OsRng),
+            || OsRng.next_u64(),
+        )
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message.root(compress::<M>)
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        MiMC5MerkleCircuit { path: message }
+    }
+
+    fn prove(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: Self::Circuit,
+        digest: &Self::Digest,
+    ) -> Vec<u8> {
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            params,
+            pk,
+            &[circuit],
+            &[&[&[*digest]]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
+    fn verify(
+        &self,
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        proof: &[u8],
+        digest: &Self::Digest,
+    ) -> bool {
+        let strategy = SingleVerifier::new(params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof(
+            params,
+            pk.get_vk(),
+            strategy,
+            &[&[&[*digest]]],
+            &mut transcript,
+        )
+        .is_ok()
+    }
+
+    fn key_sizes(&self, params: &Self::Params, pk: &Self::ProvingKey) -> KeySizes {
+        MiMC5Benchmark::new(self.field).key_sizes(params, pk)
+    }
+
+    fn fingerprint(&self) -> String {
+        circuit_fingerprint::<M::Field, Self::Circuit>(&self.name())
+    }
+
+    fn params_to_bytes(&self, params: &Self::Params) -> io::Result<Vec<u8>> {
+        MiMC5Benchmark::new(self.field).params_to_bytes(params)
+    }
+
+    fn params_from_bytes(&self, bytes: &[u8]) -> io::Result<Self::Params> {
+        MiMC5Benchmark::new(self.field).params_from_bytes(bytes)
+    }
+}
+
+/// Runs [`MiMC5MerkleBenchmark`] for a tree of `depth`.
+pub fn run_depth<M: MiMCField>(
+    field: M,
+    depth: usize,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    halo2_bench_harness::run(&MiMC5MerkleBenchmark::new(field, depth), config)
+}
+
+/// Layout of the path circuit for a tree of `depth` on `2^k` rows.
+pub fn circuit_stats_depth<M: MiMCField>(field: M, depth: usize, k: u32) -> CircuitStats {
+    MiMC5MerkleBenchmark::new(field, depth).circuit_stats(k)
+}
@@ -0,0 +1,116 @@
+//! The circuits must agree with `mimc_halo2`'s native hashes on any message,
+//! not only on the one a benchmark run happens to draw, over every field.
+//!
+//! Every case proves the native digest with `MockProver`, so a chip that
+//! drifts from its primitive fails here rather than in a benchmark.
+
+use halo2_bench_harness::merkle::MerklePath;
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2_bench_mimc::field::{MiMCField, Pallas, Vesta};
+use halo2_bench_mimc::merkle::MiMC5MerkleBenchmark;
+use halo2_bench_mimc::MiMC5Benchmark;
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    dev::MockProver,
+    plonk::Circuit,
+};
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const CASES: u32 = 256;
+
+/// Random elements of `M`'s field, with zero, one and minus one drawn far
+/// more often than chance would.
+fn element<M: MiMCField>() -> impl Strategy<Value = M::Field> {
+    prop_oneof![
+        1 => Just(M::Field::zero()),
+        1 => Just(M::Field::one()),
+        1 => Just(-M::Field::one()),
+        7 => any::<u64>().prop_map(|seed| M::Field::random(StdRng::seed_from_u64(seed))),
+    ]
+}
+
+/// Paths of up to `max_depth` levels with arbitrary siblings.
+fn path<M: MiMCField>(max_depth: usize) -> impl Strategy<Value = MerklePath<M::Field>> {
+    let siblings = prop::collection::vec(element::<M>(), 1..=max_depth);
+    (element::<M>(), any::<u64>(), siblings).prop_map(|(leaf, index, siblings)| MerklePath {
+        leaf,
+        index: index & ((1 << siblings.len()) - 1),
+        siblings,
+    })
+}
+
+/// [`min_k`] of `bench`, computed once per benchmark rather than for every
+/// case.
+fn k<B: HashBenchmark>(bench: &B) -> u32 {
+    static K: OnceLock<Mutex<HashMap<(String, &'static str), u32>>> = OnceLock::new();
+    *K.get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry((bench.name(), bench.field()))
+        .or_insert_with(|| min_k(bench).expect("MiMC circuits report their used rows"))
+}
+
+/// Checks with `MockProver` that `bench`'s circuit for `message` accepts the
+/// instance columns `instance` makes out of the native digest.
+fn agrees<B, F>(
+    bench: &B,
+    message: B::Message,
+    instance: impl FnOnce(&B::Digest) -> Vec<Vec<F>>,
+) -> Result<(), TestCaseError>
+where
+    B: HashBenchmark,
+    B::Circuit: Circuit<F>,
+    F: FieldExt,
+{
+    let digest = bench.native_hash(&message);
+    let instance = instance(&digest);
+    let circuit = bench.circuit(message, &digest);
+    let prover = MockProver::run(k(bench), &circuit, instance).expect("synthesis should not fail");
+    prop_assert_eq!(prover.verify(), Ok(()));
+    Ok(())
+}
+
+/// The differential tests for one field, in a module named after it.
+macro_rules! differential {
+    ($module:ident, $field:ident) => {
+        mod $module {
+            use super::*;
+
+            proptest! {
+                #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+                #[test]
+                fn hash_matches_native(message in element::<$field>()) {
+                    let bench = MiMC5Benchmark::new($field);
+                    agrees(&bench, vec![message], |digests| vec![digests.clone()])?;
+                }
+
+                #[test]
+                fn private_hash_matches_native(message in element::<$field>()) {
+                    let bench = MiMC5Benchmark::private($field, 1);
+                    agrees(&bench, vec![message], |_| vec![])?;
+                }
+
+                #[test]
+                fn batch_matches_native(
+                    messages in prop::collection::vec(element::<$field>(), 1..=4),
+                ) {
+                    let bench = MiMC5Benchmark::batch($field, messages.len());
+                    agrees(&bench, messages, |digests| vec![digests.clone()])?;
+                }
+
+                #[test]
+                fn merkle_matches_native(path in path::<$field>(6)) {
+                    let bench = MiMC5MerkleBenchmark::new($field, path.depth());
+                    agrees(&bench, path, |root| vec![vec![*root]])?;
+                }
+            }
+        }
+    };
+}
+
+differential!(pallas, Pallas);
+differential!(vesta, Vesta);
@@ -0,0 +1,134 @@
+//! The circuits must reject wrong digests and tampered proofs, not only accept
+//! the right ones, over every field.
+
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2_bench_mimc::field::{MiMCField, Pallas, Vesta};
+use halo2_bench_mimc::merkle::MiMC5MerkleBenchmark;
+use halo2_bench_mimc::{MiMC5Benchmark, MiMC5HashCircuit};
+use halo2_proofs::{arithmetic::Field, dev::MockProver};
+
+/// Proves a random message with `bench`, then checks that the proof verifies
+/// against its digest but not against `wrong(digest)`, if the digest is
+/// public, and that flipping a bit anywhere in it is caught.
+fn rejects_tampering<B: HashBenchmark>(bench: &B, wrong: Option<fn(&B::Digest) -> B::Digest>) {
+    let k = min_k(bench).expect("MiMC circuits report their used rows");
+    let params = bench.setup(k);
+    let pk = bench.keygen(&params);
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    let proof = bench.prove(&params, &pk, bench.circuit(message, &digest), &digest);
+
+    assert!(bench.verify(&params, &pk, &proof, &digest));
+    if let Some(wrong) = wrong {
+        assert!(!bench.verify(&params, &pk, &proof, &wrong(&digest)));
+    }
+
+    for byte in [0, proof.len() / 2, proof.len() - 1] {
+        let mut tampered = proof.clone();
+        tampered[byte] ^= 1;
+        assert!(
+            !bench.verify(&params, &pk, &tampered, &digest),
+            "flipped byte {} of {} still verifies",
+            byte,
+            proof.len()
+        );
+    }
+}
+
+fn plus_one<F: Field>(x: &F) -> F {
+    *x + F::one()
+}
+
+fn wrong_instance<M: MiMCField>(field: M) {
+    let bench = MiMC5Benchmark::batch(field, 2);
+    let k = min_k(&bench).expect("MiMC circuits report their used rows");
+    let messages = bench.random_message();
+    let mut message_hashes = bench.native_hash(&messages);
+    let circuit = bench.circuit(messages, &message_hashes);
+
+    let prover = MockProver::run(k, &circuit, vec![message_hashes.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    message_hashes[1] = plus_one(&message_hashes[1]);
+    let prover = MockProver::run(k, &circuit, vec![message_hashes]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+fn wrong_message_hash<M: MiMCField>(field: M) {
+    let bench = MiMC5Benchmark::private(field, 2);
+    let k = min_k(&bench).expect("MiMC circuits report their used rows");
+    let messages = bench.random_message();
+    let mut message_hashes = bench.native_hash(&messages);
+
+    let circuit = MiMC5HashCircuit::<M, false> {
+        messages: messages.clone(),
+        message_hashes: message_hashes.clone(),
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    message_hashes[1] = plus_one(&message_hashes[1]);
+    let circuit = MiMC5HashCircuit::<M, false> {
+        messages,
+        message_hashes,
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+fn wrong_root<M: MiMCField>(field: M) {
+    let bench = MiMC5MerkleBenchmark::new(field, 4);
+    let k = min_k(&bench).expect("MiMC circuits report their used rows");
+    let path = bench.random_message();
+    let root = bench.native_hash(&path);
+    let circuit = bench.circuit(path, &root);
+
+    let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let prover = MockProver::run(k, &circuit, vec![vec![plus_one(&root)]]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn hash_rejects_wrong_instance() {
+    wrong_instance(Pallas);
+    wrong_instance(Vesta);
+}
+
+#[test]
+fn private_hash_rejects_wrong_message_hash() {
+    wrong_message_hash(Pallas);
+    wrong_message_hash(Vesta);
+}
+
+#[test]
+fn hash_rejects_tampered_proofs() {
+    rejects_tampering(
+        &MiMC5Benchmark::new(Pallas),
+        Some(|digests| digests.iter().map(plus_one).collect()),
+    );
+    rejects_tampering(
+        &MiMC5Benchmark::new(Vesta),
+        Some(|digests| digests.iter().map(plus_one).collect()),
+    );
+}
+
+#[test]
+fn private_hash_rejects_tampered_proofs() {
+    // The digest is not a public input, so `verify` ignores it.
+    rejects_tampering(&MiMC5Benchmark::private(Pallas, 1), None);
+    rejects_tampering(&MiMC5Benchmark::private(Vesta, 1), None);
+}
+
+#[test]
+fn merkle_rejects_wrong_root() {
+    wrong_root(Pallas);
+    wrong_root(Vesta);
+}
+
+#[test]
+fn merkle_rejects_tampered_proofs() {
+    rejects_tampering(&MiMC5MerkleBenchmark::new(Pallas, 4), Some(plus_one));
+    rejects_tampering(&MiMC5MerkleBenchmark::new(Vesta, 4), Some(plus_one));
+}
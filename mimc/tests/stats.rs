@@ -0,0 +1,79 @@
+//! `circuit_stats` must report the layout of a circuit of known shape, rows
+//! included.
+
+use halo2_bench_harness::CircuitStats;
+use halo2_bench_mimc::circuit_stats;
+use halo2_bench_mimc::field::Pallas;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::Fp,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    poly::Rotation,
+};
+
+/// Two advice, one fixed and one instance column, two gates holding three
+/// constraints, the first of degree 5, and one lookup. Synthesis fills three
+/// rows.
+#[derive(Debug, Clone, Copy, Default)]
+struct Probe;
+
+impl Circuit<Fp> for Probe {
+    type Config = Column<Advice>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Probe
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let fixed = meta.fixed_column();
+        meta.instance_column();
+        let table = meta.lookup_table_column();
+
+        meta.create_gate("degree 5", |meta| {
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![a.clone() * a.clone() * a.clone() * a * b]
+        });
+        meta.create_gate("two constraints", |meta| {
+            let cur = meta.query_advice(a, Rotation::cur());
+            let next = meta.query_advice(a, Rotation::next());
+            let fixed = meta.query_fixed(fixed, Rotation::cur());
+            vec![cur.clone() - next, cur - fixed]
+        });
+        meta.lookup(|meta| vec![(meta.query_advice(b, Rotation::cur()), table)]);
+        a
+    }
+
+    fn synthesize(&self, a: Self::Config, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "rows",
+            |mut region| {
+                for row in 0..3 {
+                    region.assign_advice(|| "a", a, row, || Value::known(Fp::from(0)))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn counts_match_probe() {
+    let stats = circuit_stats::<Pallas, _>(8, &Probe);
+    assert_eq!(
+        stats,
+        CircuitStats {
+            advice_columns: 2,
+            fixed_columns: 1,
+            instance_columns: 1,
+            gates: 2,
+            lookups: 1,
+            max_degree: 5,
+            used_rows: Some(3),
+            blinding_factors: stats.blinding_factors,
+        }
+    );
+}
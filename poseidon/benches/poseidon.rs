@@ -0,0 +1,63 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_bench_backend::{Ipa, KzgGwc, KzgShplonk};
+use halo2_bench_harness::cargo_bench::prove_and_verify;
+use halo2_bench_harness::merkle::DEPTHS;
+use halo2_bench_harness::{max_hashes, min_k};
+use halo2_bench_poseidon::batch::BatchBenchmark;
+use halo2_bench_poseidon::merkle::MerkleBenchmark;
+use halo2_bench_poseidon::sponge::SpongeBenchmark;
+use halo2_bench_poseidon::PoseidonBenchmark;
+
+const KS: [u32; 3] = [7, 8, 9];
+
+/// Message lengths for the variable-length sponge, each at its smallest `k`.
+const SPONGE_LENGTHS: [usize; 3] = [64, 256, 1024];
+
+/// Rows filled with independent hashes by the batch benchmark.
+const BATCH_K: u32 = 12;
+
+fn poseidon(c: &mut Criterion) {
+    // One entry per message length that fits in the rate.
+    prove_and_verify(c, &PoseidonBenchmark::<_, 3, 2, 1>(Ipa), &KS);
+    prove_and_verify(c, &PoseidonBenchmark::<_, 3, 2, 2>(Ipa), &KS);
+}
+
+fn poseidon_sponge(c: &mut Criterion) {
+    for len in SPONGE_LENGTHS {
+        let bench = SpongeBenchmark::<_, 3, 2>::new(Ipa, len);
+        let k = min_k(&bench).expect("the sponge reports its used rows");
+        prove_and_verify(c, &bench, &[k]);
+    }
+}
+
+/// As many width-3 hashes as fit in `2^BATCH_K` rows, in one proof.
+fn poseidon_batch(c: &mut Criterion) {
+    let bench = |n| BatchBenchmark::<_, 3, 2, 2>::new(Ipa, n);
+    let n = max_hashes(BATCH_K, bench).expect("a hash fits in 2^BATCH_K rows");
+    prove_and_verify(c, &bench(n), &[BATCH_K]);
+}
+
+/// Merkle paths of each depth in [`DEPTHS`], each at its smallest `k`.
+fn poseidon_merkle(c: &mut Criterion) {
+    for depth in DEPTHS {
+        let bench = MerkleBenchmark::new(Ipa, depth);
+        let k = min_k(&bench).expect("the Merkle path reports its used rows");
+        prove_and_verify(c, &bench, &[k]);
+    }
+}
+
+/// The same circuit under KZG on BN254, to compare against IPA on Pasta.
+fn poseidon_kzg(c: &mut Criterion) {
+    prove_and_verify(c, &PoseidonBenchmark::<_, 3, 2, 2>(KzgGwc), &KS);
+    prove_and_verify(c, &PoseidonBenchmark::<_, 3, 2, 2>(KzgShplonk), &KS);
+}
+
+criterion_group!(
+    benches,
+    poseidon,
+    poseidon_sponge,
+    poseidon_batch,
+    poseidon_merkle,
+    poseidon_kzg
+);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+//! Many independent fixed-length hashes in one proof.
+//!
+//! [`HashCircuit`] proves a single hash, so its proving time is dominated by
+//! the fixed cost of the proof. [`BatchCircuit`] lays out `n` hashes with the
+//! same columns and exposes every digest in the instance column, which gives
+//! the amortized cost per hash.
+
+use crate::{circuit_fingerprint, HashCircuit, MyConfig, MySpec};
+use ff::{Field, PrimeField};
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::{max_hashes, BenchResult, CircuitStats, HashBenchmark, RunConfig};
+use halo2_poseidon::poseidon::{
+    primitives::{self as poseidon, ConstantLength, Spec},
+    Hash, Pow5Chip,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error, ProvingKey},
+};
+use rand::rngs::OsRng;
+use std::marker::PhantomData;
+
+/// Circuit proving that row `i` of the instance column is the hash of the
+/// `i`-th of `n` messages.
+#[derive(Clone)]
+pub struct BatchCircuit<F, S, const WIDTH: usize, const RATE: usize, const L: usize> {
+    messages: Value<Vec<[F; L]>>,
+    n: usize,
+    _spec: PhantomData<S>,
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize> Circuit<F>
+    for BatchCircuit<F, S, WIDTH, RATE, L>
+where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE> + Copy + Clone,
+{
+    type Config = MyConfig<F, WIDTH, RATE, L>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            messages: Value::unknown(),
+            n: self.n,
+            _spec: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        HashCircuit::<F, S, WIDTH, RATE, L>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        for i in 0..self.n {
+            let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+            let message = layouter.assign_region(
+                || format!("load message {}", i),
+                |mut region| {
+                    let message_word = |j: usize| {
+                        let value = self.messages.as_ref().map(|messages| messages[i][j]);
+                        region.assign_advice(
+                            || format!("load message_{}_{}", i, j),
+                            config.input[j],
+                            0,
+                            || value,
+                        )
+                    };
+
+                    let message: Result<Vec<_>, Error> = (0..L).map(message_word).collect();
+                    Ok(message?.try_into().unwrap())
+                },
+            )?;
+
+            let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
+                chip,
+                layouter.namespace(|| format!("init {}", i)),
+            )?;
+            let output = hasher.hash(layouter.namespace(|| format!("hash {}", i)), message)?;
+
+            layouter.constrain_instance(output.cell(), config.expected, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// `n` fixed-length Poseidon hashes of `L`-element messages, proven with `B`.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchBenchmark<B, const WIDTH: usize, const RATE: usize, const L: usize> {
+    backend: B,
+    n: usize,
+}
+
+impl<B, const WIDTH: usize, const RATE: usize, const L: usize> BatchBenchmark<B, WIDTH, RATE, L> {
+    /// Proves `n` hashes at once.
+    pub fn new(backend: B, n: usize) -> Self {
+        Self { backend, n }
+    }
+
+    fn empty_circuit(&self) -> <Self as HashBenchmark>::Circuit
+    where
+        B: Backend,
+    {
+        BatchCircuit {
+            messages: Value::unknown(),
+            n: self.n,
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<B: Backend, const WIDTH: usize, const RATE: usize, const L: usize> HashBenchmark
+    for BatchBenchmark<B, WIDTH, RATE, L>
+{
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = Vec<[B::Field; L]>;
+    type Digest = Vec<B::Field>;
+    type Circuit = BatchCircuit<B::Field, MySpec<WIDTH, RATE>, WIDTH, RATE, L>;
+
+    fn name(&self) -> String {
+        format!("poseidon-batch-w{}-r{}-l{}-n{}", WIDTH, RATE, L, self.n)
+    }
+
+    fn message_len(&self) -> usize {
+        L
+    }
+
+    fn hashes(&self) -> usize {
+        self.n
+    }
+
+    fn random_message(&self) -> Self::Message {
+        (0..self.n)
+            .map(|_| [(); L].map(|_| B::Field::random(OsRng)))
+            .collect()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message
+            .iter()
+            .map(|message| {
+                poseidon::Hash::<_, MySpec<WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init()
+                    .hash(*message)
+            })
+            .collect()
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        BatchCircuit {
+            messages: Value::known(message),
+            n: self.n,
+            _spec: PhantomData,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let constants = <MySpec<WIDTH, RATE> as Spec<B::Field, WIDTH, RATE>>::constants();
+        circuit_fingerprint::<B::Field, Self::Circuit>(constants)
+    }
+
+    forward_to_backend!(backend, |digest| digest);
+}
+
+/// Runs [`BatchBenchmark`] at full rate for `width`, or returns `None` if
+/// `width` is not one of [`WIDTHS`](crate::WIDTHS).
+///
+/// Without `n`, as many hashes are batched as fit in `2^k` rows; the inner
+/// result fails if `config.k` is unset, if none fit (see [`max_hashes`]) or as
+/// [`halo2_bench_harness::run`] does.
+pub fn run_width<B: Backend>(
+    backend: B,
+    width: usize,
+    n: Option<usize>,
+    config: &RunConfig,
+) -> Option<Result<BenchResult, String>> {
+    with_width!(width, W => {
+        let bench = |n| BatchBenchmark::<_, W, { W - 1 }, { W - 1 }>::new(backend, n);
+        match n {
+            Some(n) => Ok(n),
+            None => config
+                .k
+                .ok_or_else(|| "filling the rows needs k".to_string())
+                .and_then(|k| max_hashes(k, bench)),
+        }
+        .and_then(|n| halo2_bench_harness::run(&bench(n), config))
+    })
+}
+
+/// Layout of `n` hashes at full rate for `width` on `2^k` rows, or `None` if
+/// `width` is not one of [`WIDTHS`](crate::WIDTHS).
+pub fn circuit_stats_width<B: Backend>(
+    backend: B,
+    width: usize,
+    n: usize,
+    k: u32,
+) -> Option<CircuitStats> {
+    with_width!(width, W => {
+        BatchBenchmark::<_, W, { W - 1 }, { W - 1 }>::new(backend, n).circuit_stats(k)
+    })
+}
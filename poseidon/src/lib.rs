@@ -0,0 +1,293 @@
+use ff::{Field, FromUniformBytes, PrimeField, PrimeFieldBits};
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::{BenchResult, CircuitStats, HashBenchmark, RunConfig};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, ProvingKey},
+};
+
+use halo2_poseidon::poseidon::{
+    primitives::{self as poseidon, generate_constants, ConstantLength, Mds, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use rand::rngs::OsRng;
+
+/// Evaluates `$body` with the constant `$width` set to `width`, or gives
+/// `None` if `width` is not one of [`WIDTHS`].
+///
+/// Const generics cannot be picked at runtime, so `$body` is monomorphized
+/// once per width. Patterns cannot read a constant, hence the widths are
+/// spelled out again.
+macro_rules! with_width {
+    ($width_value:expr, $width:ident => $body:expr) => {
+        with_width!(@ $width_value, $width => $body; 3, 4, 5, 9, 12)
+    };
+    (@ $width_value:expr, $width:ident => $body:expr; $($w:literal),*) => {
+        match $width_value {
+            $($w => {
+                const $width: usize = $w;
+                Some($body)
+            })*
+            _ => None,
+        }
+    };
+}
+
+pub mod batch;
+pub mod merkle;
+pub mod security;
+pub mod sponge;
+
+/// Fields [`MySpec`] can be instantiated over.
+pub trait SpecField: FromUniformBytes<64> + PrimeFieldBits + Ord + 'static {}
+
+impl<F: FromUniformBytes<64> + PrimeFieldBits + Ord + 'static> SpecField for F {}
+
+#[derive(Clone, Copy)]
+pub struct HashCircuit<F, S, const WIDTH: usize, const RATE: usize, const L: usize>
+where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    message: Value<[F; L]>,
+    _spec: PhantomData<S>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MyConfig<F: PrimeField, const WIDTH: usize, const RATE: usize, const L: usize> {
+    input: [Column<Advice>; L],
+    expected: Column<Instance>,
+    poseidon_config: Pow5Config<F, WIDTH, RATE>,
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize> Circuit<F>
+    for HashCircuit<F, S, WIDTH, RATE, L>
+where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE> + Copy + Clone,
+{
+    type Config = MyConfig<F, WIDTH, RATE, L>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: Value::unknown(),
+            _spec: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let expected = meta.instance_column();
+        meta.enable_equality(expected);
+        let partial_sbox = meta.advice_column();
+
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+
+        meta.enable_constant(rc_b[0]);
+
+        Self::Config {
+            input: state[..L].try_into().unwrap(),
+            expected,
+            poseidon_config: Pow5Chip::configure::<S>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+        let message = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                let message_word = |i: usize| {
+                    let value = self.message.map(|message_vals| message_vals[i]);
+                    region.assign_advice(
+                        || format!("load message_{}", i),
+                        config.input[i],
+                        0,
+                        || value,
+                    )
+                };
+
+                let message: Result<Vec<_>, Error> = (0..L).map(message_word).collect();
+                Ok(message?.try_into().unwrap())
+            },
+        )?;
+
+        let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
+            chip,
+            layouter.namespace(|| "init"),
+        )?;
+        let output = hasher.hash(layouter.namespace(|| "hash"), message)?;
+
+        layouter.constrain_instance(output.cell(), config.expected, 0)
+    }
+}
+
+/// Poseidon with `x^5`, with round numbers and MDS matrix chosen for 128-bit
+/// security at the given width over whichever [`SpecField`] it is used with.
+#[derive(Debug, Clone, Copy)]
+pub struct MySpec<const WIDTH: usize, const RATE: usize>;
+
+impl<F: SpecField, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE>
+    for MySpec<WIDTH, RATE>
+{
+    fn full_rounds() -> usize {
+        security::round_numbers::<F>(WIDTH).full
+    }
+
+    fn partial_rounds() -> usize {
+        security::round_numbers::<F>(WIDTH).partial
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime([security::ALPHA])
+    }
+
+    fn secure_mds() -> usize {
+        security::secure_mds::<F, WIDTH, RATE>()
+    }
+
+    fn constants() -> (Vec<[F; WIDTH]>, Mds<F, WIDTH>, Mds<F, WIDTH>) {
+        generate_constants::<_, Self, WIDTH, RATE>()
+    }
+}
+
+/// Fixed-length Poseidon of an `L`-element message, proven with `B`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoseidonBenchmark<B, const WIDTH: usize, const RATE: usize, const L: usize>(pub B);
+
+impl<B, const WIDTH: usize, const RATE: usize, const L: usize>
+    PoseidonBenchmark<B, WIDTH, RATE, L>
+{
+    fn empty_circuit(&self) -> <Self as HashBenchmark>::Circuit
+    where
+        B: Backend,
+    {
+        HashCircuit {
+            message: Value::unknown(),
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<B: Backend, const WIDTH: usize, const RATE: usize, const L: usize> HashBenchmark
+    for PoseidonBenchmark<B, WIDTH, RATE, L>
+{
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = [B::Field; L];
+    type Digest = B::Field;
+    type Circuit = HashCircuit<B::Field, MySpec<WIDTH, RATE>, WIDTH, RATE, L>;
+
+    fn name(&self) -> String {
+        format!("poseidon-w{}-r{}-l{}", WIDTH, RATE, L)
+    }
+
+    fn message_len(&self) -> usize {
+        L
+    }
+
+    fn random_message(&self) -> Self::Message {
+        let mut rng = OsRng;
+        (0..L)
+            .map(|_| B::Field::random(&mut rng))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        poseidon::Hash::<_, MySpec<WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init()
+            .hash(*message)
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        // The digest is not witnessed; it reaches the circuit as its public input.
+        HashCircuit {
+            message: Value::known(message),
+            _spec: PhantomData,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let constants = <MySpec<WIDTH, RATE> as Spec<B::Field, WIDTH, RATE>>::constants();
+        circuit_fingerprint::<B::Field, Self::Circuit>(constants)
+    }
+
+    forward_to_backend!(0, |digest| &[*digest]);
+}
+
+/// Gates and columns of `C` together with the Poseidon constants it assigns,
+/// which only show up in the fixed columns once synthesized.
+fn circuit_fingerprint<F: PrimeField, C: Circuit<F>>(constants: impl Debug) -> String {
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+    format!("{:?} {:?}", meta, constants)
+}
+
+/// State widths covered by [`sweep`] and [`run_width`].
+pub const WIDTHS: [usize; 5] = [3, 4, 5, 9, 12];
+
+/// Runs one [`PoseidonBenchmark`] per `(WIDTH, RATE, L)` triple, stopping at
+/// the first that fails.
+///
+/// Const generics cannot be picked at runtime, so every point of the sweep is
+/// its own monomorphization.
+macro_rules! run_each {
+    ($backend:expr, $config:expr; $(($width:literal, $rate:literal, $l:literal)),* $(,)?) => {
+        Ok(vec![$(halo2_bench_harness::run(
+            &PoseidonBenchmark::<_, $width, $rate, $l>($backend),
+            $config,
+        )?),*])
+    };
+}
+
+/// Runs every width in [`WIDTHS`] with a single-element and a full-rate
+/// message, so the cost per absorbed element can be compared across widths.
+pub fn sweep<B: Backend>(backend: B, config: &RunConfig) -> Result<Vec<BenchResult>, String> {
+    run_each!(backend, config;
+        (3, 2, 1), (3, 2, 2),
+        (4, 3, 1), (4, 3, 3),
+        (5, 4, 1), (5, 4, 4),
+        (9, 8, 1), (9, 8, 8),
+        (12, 11, 1), (12, 11, 11),
+    )
+}
+
+/// Runs the full-rate benchmark for `width`, or returns `None` if `width` is
+/// not one of [`WIDTHS`]; the inner result fails as [`halo2_bench_harness::run`]
+/// does.
+pub fn run_width<B: Backend>(
+    backend: B,
+    width: usize,
+    config: &RunConfig,
+) -> Option<Result<BenchResult, String>> {
+    with_width!(width, W => halo2_bench_harness::run(
+        &PoseidonBenchmark::<_, W, { W - 1 }, { W - 1 }>(backend),
+        config,
+    ))
+}
+
+/// Layout of the circuit [`run_width`] proves for `width` on `2^k` rows, or
+/// `None` if `width` is not one of [`WIDTHS`].
+pub fn circuit_stats_width<B: Backend>(backend: B, width: usize, k: u32) -> Option<CircuitStats> {
+    with_width!(width, W => {
+        PoseidonBenchmark::<_, W, { W - 1 }, { W - 1 }>(backend).circuit_stats(k)
+    })
+}
@@ -0,0 +1,260 @@
+//! Merkle path verification with Poseidon as the two-to-one hash.
+//!
+//! Each level orders the current node and its sibling by the matching bit of
+//! the leaf index with a conditional swap gate, then hashes the pair with
+//! width-3 [`ConstantLength<2>`] Poseidon. The root is the only public input;
+//! the leaf, siblings and index stay private.
+
+use crate::{circuit_fingerprint, MySpec, SpecField};
+use ff::{Field, PrimeField};
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::merkle::{MerklePath, MerkleTree};
+use halo2_bench_harness::{BenchResult, CircuitStats, HashBenchmark, RunConfig};
+use halo2_poseidon::poseidon::{
+    primitives::{self as poseidon, ConstantLength, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, ProvingKey,
+        Selector,
+    },
+    poly::Rotation,
+};
+use rand::{rngs::OsRng, RngCore};
+use std::marker::PhantomData;
+
+/// Poseidon of `[left, right]`, the compression the tree is built with.
+pub fn compress<F: SpecField>(left: &F, right: &F) -> F {
+    poseidon::Hash::<_, MySpec<3, 2>, ConstantLength<2>, 3, 2>::init().hash([*left, *right])
+}
+
+/// Circuit proving that the instance column holds the root of `path`.
+#[derive(Clone)]
+pub struct MerkleCircuit<F, S> {
+    path: Value<MerklePath<F>>,
+    depth: usize,
+    _spec: PhantomData<S>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MerkleConfig<F: PrimeField> {
+    /// Node, sibling and index bit of a level, with the ordered pair on the
+    /// next row. These are the Poseidon state columns.
+    advice: [Column<Advice>; 3],
+    swap: Selector,
+    root: Column<Instance>,
+    poseidon_config: Pow5Config<F, 3, 2>,
+}
+
+impl<F, S> Circuit<F> for MerkleCircuit<F, S>
+where
+    F: PrimeField,
+    S: Spec<F, 3, 2> + Copy + Clone,
+{
+    type Config = MerkleConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            path: Value::unknown(),
+            depth: self.depth,
+            _spec: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let state = [(); 3].map(|_| meta.advice_column());
+        let root = meta.instance_column();
+        meta.enable_equality(root);
+        let partial_sbox = meta.advice_column();
+
+        let rc_a = [(); 3].map(|_| meta.fixed_column());
+        let rc_b = [(); 3].map(|_| meta.fixed_column());
+
+        meta.enable_constant(rc_b[0]);
+
+        let swap = meta.selector();
+        meta.create_gate("swap", |meta| {
+            let swap = meta.query_selector(swap);
+            let node = meta.query_advice(state[0], Rotation::cur());
+            let sibling = meta.query_advice(state[1], Rotation::cur());
+            let bit = meta.query_advice(state[2], Rotation::cur());
+            let left = meta.query_advice(state[0], Rotation::next());
+            let right = meta.query_advice(state[1], Rotation::next());
+
+            let one = Expression::Constant(F::ONE);
+            vec![
+                swap.clone() * bit.clone() * (one - bit.clone()),
+                swap.clone()
+                    * (left.clone() - node.clone() - bit * (sibling.clone() - node.clone())),
+                swap * (left + right - node - sibling),
+            ]
+        });
+
+        Self::Config {
+            advice: state,
+            swap,
+            root,
+            poseidon_config: Pow5Chip::configure::<S>(meta, state, partial_sbox, rc_a, rc_b),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let mut node = layouter.assign_region(
+            || "load leaf",
+            |mut region| {
+                let leaf = self.path.as_ref().map(|path| path.leaf);
+                region.assign_advice(|| "leaf", config.advice[0], 0, || leaf)
+            },
+        )?;
+
+        for height in 0..self.depth {
+            let pair = layouter.assign_region(
+                || format!("swap {}", height),
+                |mut region| swap(&config, &mut region, &node, &self.path, height),
+            )?;
+
+            let chip = Pow5Chip::construct(config.poseidon_config.clone());
+            let hasher = Hash::<_, _, S, ConstantLength<2>, 3, 2>::init(
+                chip,
+                layouter.namespace(|| format!("init {}", height)),
+            )?;
+            node = hasher.hash(layouter.namespace(|| format!("hash {}", height)), pair)?;
+        }
+
+        layouter.constrain_instance(node.cell(), config.root, 0)
+    }
+}
+
+/// Assigns `node` and its sibling at `height`, followed by the two in the
+/// order they are hashed.
+fn swap<F: PrimeField>(
+    config: &MerkleConfig<F>,
+    region: &mut Region<'_, F>,
+    node: &AssignedCell<F, F>,
+    path: &Value<MerklePath<F>>,
+    height: usize,
+) -> Result<[AssignedCell<F, F>; 2], Error> {
+    config.swap.enable(region, 0)?;
+
+    let sibling = path.as_ref().map(|path| path.siblings[height]);
+    let is_right = path.as_ref().map(|path| path.is_right(height));
+    node.copy_advice(|| "node", region, config.advice[0], 0)?;
+    region.assign_advice(|| "sibling", config.advice[1], 0, || sibling)?;
+    region.assign_advice(
+        || "index bit",
+        config.advice[2],
+        0,
+        || is_right.map(|is_right| F::from(is_right as u64)),
+    )?;
+
+    let (left, right) = node
+        .value()
+        .copied()
+        .zip(sibling)
+        .zip(is_right)
+        .map(|((node, sibling), is_right)| {
+            if is_right {
+                (sibling, node)
+            } else {
+                (node, sibling)
+            }
+        })
+        .unzip();
+    let left = region.assign_advice(|| "left", config.advice[0], 1, || left)?;
+    let right = region.assign_advice(|| "right", config.advice[1], 1, || right)?;
+    Ok([left, right])
+}
+
+/// Membership of a leaf in a Poseidon Merkle tree of the given depth, proven
+/// with `B`.
+///
+/// Every level is one hash, so [`hashes`](HashBenchmark::hashes) is the depth.
+#[derive(Debug, Clone, Copy)]
+pub struct MerkleBenchmark<B> {
+    backend: B,
+    depth: usize,
+}
+
+impl<B: Backend> MerkleBenchmark<B> {
+    pub fn new(backend: B, depth: usize) -> Self {
+        Self { backend, depth }
+    }
+
+    fn empty_circuit(&self) -> <Self as HashBenchmark>::Circuit {
+        MerkleCircuit {
+            path: Value::unknown(),
+            depth: self.depth,
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<B: Backend> HashBenchmark for MerkleBenchmark<B> {
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = MerklePath<B::Field>;
+    type Digest = B::Field;
+    type Circuit = MerkleCircuit<B::Field, MySpec<3, 2>>;
+
+    fn name(&self) -> String {
+        format!("poseidon-merkle-d{}", self.depth)
+    }
+
+    fn message_len(&self) -> usize {
+        2
+    }
+
+    fn hashes(&self) -> usize {
+        self.depth
+    }
+
+    fn random_message(&self) -> Self::Message {
+        MerkleTree::random_path(
+            self.depth,
+            B::Field::ZERO,
+            compress,
+            || B::Field::random(OsRng),
+            || OsRng.next_u64(),
+        )
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        message.root(compress)
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        MerkleCircuit {
+            path: Value::known(message),
+            depth: self.depth,
+            _spec: PhantomData,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let constants = <MySpec<3, 2> as Spec<B::Field, 3, 2>>::constants();
+        circuit_fingerprint::<B::Field, Self::Circuit>(constants)
+    }
+
+    forward_to_backend!(backend, |digest| &[*digest]);
+}
+
+/// Runs [`MerkleBenchmark`] for a tree of `depth`.
+pub fn run_depth<B: Backend>(
+    backend: B,
+    depth: usize,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    halo2_bench_harness::run(&MerkleBenchmark::new(backend, depth), config)
+}
+
+/// Layout of the path circuit for a tree of `depth` on `2^k` rows.
+pub fn circuit_stats_depth<B: Backend>(backend: B, depth: usize, k: u32) -> CircuitStats {
+    MerkleBenchmark::new(backend, depth).circuit_stats(k)
+}
@@ -0,0 +1,391 @@
+//! Round numbers and MDS selection for a 128-bit secure Poseidon instance.
+//!
+//! Both follow the reference scripts shipped with the Poseidon paper
+//! (`calc_round_numbers.py` and `generate_parameters_grain.sage`), specialised
+//! to the `x^5` S-box used by [`Pow5Chip`](halo2_poseidon::poseidon::Pow5Chip).
+//!
+//! For width 3 over Pasta this reproduces `P128Pow5T3` exactly: 8 full and 56
+//! partial rounds, and the first MDS candidate. Wider states need 56 or 57
+//! partial rounds over both Pasta and BN254. Tables produced with other
+//! revisions of the script differ; circomlib, for one, uses 57 partial rounds
+//! for width 3.
+
+use ff::{FromUniformBytes, PrimeFieldBits};
+use halo2_poseidon::poseidon::primitives::{generate_constants, Mds, Spec};
+use std::any::TypeId;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Mutex, OnceLock};
+
+/// Targeted security level in bits.
+pub const SECURITY_BITS: f64 = 128.0;
+
+/// Exponent of the S-box.
+pub const ALPHA: u64 = 5;
+
+/// Number of full and partial rounds, security margin included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundNumbers {
+    pub full: usize,
+    pub partial: usize,
+}
+
+/// Cheapest round numbers for a state of `width` elements of `F`.
+///
+/// Searches the smallest `R_F`, `R_P` satisfying the statistical,
+/// interpolation and Gröbner basis bounds of the paper (including the extra
+/// bound of ePrint 2023/537), then adds two full rounds and 7.5% partial
+/// rounds as margin. Candidates are ranked by S-box count, `width * R_F + R_P`.
+pub fn round_numbers<F: PrimeFieldBits + 'static>(width: usize) -> RoundNumbers {
+    static CACHE: OnceLock<Mutex<HashMap<(TypeId, usize), RoundNumbers>>> = OnceLock::new();
+    cached(&CACHE, (TypeId::of::<F>(), width), || {
+        let field = FieldSize::of::<F>();
+        let mut best: Option<(usize, RoundNumbers)> = None;
+        for full in (4..100).step_by(2) {
+            let Some(partial) = (1..500).find(|&partial| field.is_secure(width, full, partial))
+            else {
+                continue;
+            };
+            let rounds = RoundNumbers {
+                full: full + 2,
+                partial: (partial as f64 * 1.075).ceil() as usize,
+            };
+            let cost = width * rounds.full + rounds.partial;
+            if best.map_or(true, |(best_cost, _)| cost < best_cost) {
+                best = Some((cost, rounds));
+            }
+        }
+        best.map(|(_, rounds)| rounds)
+            .unwrap_or_else(|| panic!("no secure round numbers for width {}", width))
+    })
+}
+
+/// Index of the first MDS matrix generated for `Spec` parameters with these
+/// round numbers that admits no infinitely long invariant subspace trails.
+///
+/// Runs the three checks of the reference script:
+///
+/// 1. for every `1 <= i < WIDTH`, the minimal polynomial of `M^i` is
+///    irreducible of degree `WIDTH`. Its characteristic polynomial being
+///    irreducible is equivalent, which is what is tested;
+/// 2. no proper subspace containing the S-box input of the partial rounds,
+///    the first unit vector, is invariant under `M`;
+/// 3. the same holds for `M^r`, for every `2 <= r <= 4 * WIDTH`.
+pub fn secure_mds<F, const WIDTH: usize, const RATE: usize>() -> usize
+where
+    F: FromUniformBytes<64> + PrimeFieldBits + Ord + 'static,
+{
+    static CACHE: OnceLock<Mutex<HashMap<(TypeId, usize), usize>>> = OnceLock::new();
+    cached(&CACHE, (TypeId::of::<F>(), WIDTH), || {
+        (0..)
+            .find(|&index| {
+                PROBED_MDS.with(|probed| probed.set(index));
+                let (_, mds, _) = generate_constants::<F, MdsProbe<F>, WIDTH, RATE>();
+                is_secure_mds(&mds)
+            })
+            .unwrap()
+    })
+}
+
+fn cached<K, V>(cache: &'static OnceLock<Mutex<HashMap<K, V>>>, key: K, f: impl FnOnce() -> V) -> V
+where
+    K: Eq + std::hash::Hash,
+    V: Copy,
+{
+    let cache = cache.get_or_init(Default::default);
+    if let Some(value) = cache.lock().unwrap().get(&key) {
+        return *value;
+    }
+    // Computed without holding the lock, as `f` may fill other caches.
+    let value = f();
+    *cache.lock().unwrap().entry(key).or_insert(value)
+}
+
+/// Bit length and binary logarithm of the field modulus.
+struct FieldSize {
+    bits: usize,
+    log2: f64,
+}
+
+impl FieldSize {
+    fn of<F: PrimeFieldBits>() -> Self {
+        let bits = F::char_le_bits().into_iter().collect::<Vec<bool>>();
+        let bits = &bits[..=bits.iter().rposition(|bit| *bit).unwrap()];
+        // The top 64 bits are plenty for an `f64`.
+        let top = bits
+            .iter()
+            .rev()
+            .take(64)
+            .fold(0u64, |acc, bit| (acc << 1) | *bit as u64);
+        let shift = bits.len().saturating_sub(64);
+        Self {
+            bits: bits.len(),
+            log2: (top as f64).log2() + shift as f64,
+        }
+    }
+
+    /// Whether `full` and `partial` rounds resist every attack considered.
+    fn is_secure(&self, width: usize, full: usize, partial: usize) -> bool {
+        let t = width as f64;
+        let (r_f, r_p) = (full as f64, partial as f64);
+        let m = SECURITY_BITS;
+        let alpha = ALPHA as f64;
+        let log_alpha_2 = 2f64.log(alpha);
+
+        let statistical = if m <= (self.log2 - (alpha - 1.0) / 2.0).floor() * (t + 1.0) {
+            6.0
+        } else {
+            10.0
+        };
+        let interpolation =
+            1.0 + (log_alpha_2 * m.min(self.bits as f64)).ceil() + t.log(alpha).ceil() - r_p;
+        let groebner_1 = log_alpha_2 * m.min(self.log2) - r_p;
+        let groebner_2 = t - 1.0 + log_alpha_2 * (m / (t + 1.0)).min(self.log2 / 2.0) - r_p;
+        let groebner_3 = (t - 2.0 + m / (2.0 * alpha.log2()) - r_p) / (t - 1.0);
+        let bound = [
+            statistical,
+            interpolation,
+            groebner_1,
+            groebner_2,
+            groebner_3,
+        ]
+        .into_iter()
+        .map(f64::ceil)
+        .fold(f64::NEG_INFINITY, f64::max);
+        if r_f < bound {
+            return false;
+        }
+
+        // Gröbner basis attack of ePrint 2023/537.
+        let r = (t / 3.0).floor();
+        let over = (r_f - 1.0) * t + r_p + r + r * (r_f / 2.0) + r_p + alpha;
+        let under = r * (r_f / 2.0) + r_p + alpha;
+        (2.0 * log2_binomial(over as u64, under as u64)).ceil() >= m
+    }
+}
+
+fn log2_binomial(n: u64, k: u64) -> f64 {
+    (1..=k.min(n - k))
+        .map(|i| ((n - i + 1) as f64 / i as f64).log2())
+        .sum()
+}
+
+thread_local! {
+    static PROBED_MDS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Spec whose only purpose is to let `generate_constants` produce the MDS
+/// candidate selected through [`PROBED_MDS`].
+struct MdsProbe<F>(PhantomData<F>);
+
+impl<F> fmt::Debug for MdsProbe<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MdsProbe")
+    }
+}
+
+impl<F, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE> for MdsProbe<F>
+where
+    F: FromUniformBytes<64> + PrimeFieldBits + Ord + 'static,
+{
+    fn full_rounds() -> usize {
+        round_numbers::<F>(WIDTH).full
+    }
+
+    fn partial_rounds() -> usize {
+        round_numbers::<F>(WIDTH).partial
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime([ALPHA])
+    }
+
+    fn secure_mds() -> usize {
+        PROBED_MDS.with(Cell::get)
+    }
+
+    fn constants() -> (Vec<[F; WIDTH]>, Mds<F, WIDTH>, Mds<F, WIDTH>) {
+        unreachable!("only used through generate_constants")
+    }
+}
+
+fn is_secure_mds<F: PrimeFieldBits, const T: usize>(mds: &Mds<F, T>) -> bool {
+    let mut power = *mds;
+    for i in 1..=4 * T {
+        if i > 1 {
+            power = mat_mul(&power, mds);
+        }
+        if i < T && !is_irreducible(&char_poly(&power)) {
+            return false;
+        }
+        if !first_unit_spans(&power) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Whether the orbit `e_0, M e_0, M^2 e_0, ...` of the first unit vector
+/// spans the whole space, so that no proper subspace containing `e_0` is
+/// invariant under `M`. The orbit stops growing as soon as one step adds
+/// nothing, so its first `T` vectors decide.
+fn first_unit_spans<F: PrimeFieldBits, const T: usize>(m: &Mds<F, T>) -> bool {
+    let mut v = [F::ZERO; T];
+    v[0] = F::ONE;
+    let mut orbit = Vec::with_capacity(T);
+    for _ in 0..T {
+        let next = std::array::from_fn(|i| (0..T).map(|j| m[i][j] * v[j]).sum());
+        orbit.push(v);
+        v = next;
+    }
+    rank(orbit) == T
+}
+
+/// Rank of the matrix with the given rows, by Gaussian elimination.
+fn rank<F: PrimeFieldBits, const T: usize>(mut rows: Vec<[F; T]>) -> usize {
+    let mut rank = 0;
+    for col in 0..T {
+        let Some(pivot) = (rank..rows.len()).find(|&row| rows[row][col] != F::ZERO) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let inv = rows[rank][col].invert().unwrap();
+        for row in rank + 1..rows.len() {
+            let factor = rows[row][col] * inv;
+            for j in col..T {
+                let sub = factor * rows[rank][j];
+                rows[row][j] -= sub;
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+fn modulus_limbs<F: PrimeFieldBits>() -> Vec<u64> {
+    let bits = F::char_le_bits().into_iter().collect::<Vec<bool>>();
+    bits.chunks(64)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(0u64, |acc, bit| (acc << 1) | *bit as u64)
+        })
+        .collect()
+}
+
+fn mat_mul<F: PrimeFieldBits, const T: usize>(a: &Mds<F, T>, b: &Mds<F, T>) -> Mds<F, T> {
+    let mut product = [[F::ZERO; T]; T];
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = (0..T).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    product
+}
+
+/// Monic characteristic polynomial, lowest coefficient first (Faddeev–LeVerrier).
+fn char_poly<F: PrimeFieldBits, const T: usize>(m: &Mds<F, T>) -> Vec<F> {
+    let mut coeffs = vec![F::ZERO; T + 1];
+    coeffs[T] = F::ONE;
+    let mut acc = [[F::ZERO; T]; T];
+    for k in 1..=T {
+        for (i, row) in acc.iter_mut().enumerate() {
+            row[i] += coeffs[T - k + 1];
+        }
+        acc = mat_mul(m, &acc);
+        let trace: F = (0..T).map(|i| acc[i][i]).sum();
+        coeffs[T - k] = -trace * F::from(k as u64).invert().unwrap();
+    }
+    coeffs
+}
+
+/// Rabin's irreducibility test for a monic `f` of degree `n` over `F_p`:
+/// `x^(p^n) = x (mod f)` and `gcd(x^(p^(n/q)) - x, f) = 1` for every prime
+/// `q` dividing `n`.
+///
+/// Coefficients are listed lowest first.
+pub fn is_irreducible<F: PrimeFieldBits>(f: &[F]) -> bool {
+    let modulus = modulus_limbs::<F>();
+    let n = f.len() - 1;
+    let x = {
+        let mut x = vec![F::ZERO; n.max(2)];
+        x[1] = F::ONE;
+        poly_rem(&x, f)
+    };
+    // frobenius[j] = x^(p^j) mod f
+    let mut frobenius = vec![x.clone()];
+    for j in 1..=n {
+        let next = poly_pow_mod(&frobenius[j - 1], &modulus, f);
+        frobenius.push(next);
+    }
+    if frobenius[n] != x {
+        return false;
+    }
+    (2..=n)
+        .filter(|q| n % q == 0 && (2..*q).all(|d| q % d != 0))
+        .all(|q| {
+            let mut g = frobenius[n / q].clone();
+            g.resize(n.max(2), F::ZERO);
+            g[1] -= F::ONE;
+            poly_gcd(trim(g), f.to_vec()).len() == 1
+        })
+}
+
+fn poly_pow_mod<F: PrimeFieldBits>(base: &[F], exp: &[u64], f: &[F]) -> Vec<F> {
+    let mut result = vec![F::ONE];
+    for limb in exp.iter().rev() {
+        for bit in (0..64).rev() {
+            result = poly_rem(&poly_mul(&result, &result), f);
+            if (limb >> bit) & 1 == 1 {
+                result = poly_rem(&poly_mul(&result, base), f);
+            }
+        }
+    }
+    result
+}
+
+fn poly_mul<F: PrimeFieldBits>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut product = vec![F::ZERO; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] += *a * b;
+        }
+    }
+    product
+}
+
+/// Remainder of `a` modulo a non-zero `b`, without trailing zero coefficients.
+fn poly_rem<F: PrimeFieldBits>(a: &[F], b: &[F]) -> Vec<F> {
+    let b = trim(b.to_vec());
+    let lead_inv = b.last().unwrap().invert().unwrap();
+    let mut rem = trim(a.to_vec());
+    while rem.len() >= b.len() && !(rem.len() == 1 && rem[0] == F::ZERO) {
+        let shift = rem.len() - b.len();
+        let factor = *rem.last().unwrap() * lead_inv;
+        for (i, coeff) in b.iter().enumerate() {
+            rem[shift + i] -= factor * coeff;
+        }
+        rem = trim(rem);
+    }
+    rem
+}
+
+fn poly_gcd<F: PrimeFieldBits>(mut a: Vec<F>, mut b: Vec<F>) -> Vec<F> {
+    while !(b.len() == 1 && b[0] == F::ZERO) {
+        let rem = poly_rem(&a, &b);
+        a = b;
+        b = rem;
+    }
+    a
+}
+
+/// Drops high zero coefficients, keeping at least the constant term.
+fn trim<F: PrimeFieldBits>(mut a: Vec<F>) -> Vec<F> {
+    while a.len() > 1 && *a.last().unwrap() == F::ZERO {
+        a.pop();
+    }
+    a
+}
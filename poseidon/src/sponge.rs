@@ -0,0 +1,271 @@
+//! Variable-length Poseidon, absorbing `RATE` elements per permutation.
+//!
+//! `HashCircuit` loads the whole message into one row of state columns, which
+//! caps it at `RATE` elements. Here the message is spread over as many rows as
+//! needed and fed through the gadget's [`Sponge`] one word at a time, padded
+//! with [`VariableLength`].
+
+use crate::{circuit_fingerprint, HashCircuit, MyConfig, MySpec};
+use ff::{Field, PrimeField};
+use halo2_bench_backend::{forward_to_backend, Backend};
+use halo2_bench_harness::{BenchResult, CircuitStats, HashBenchmark, RunConfig};
+use halo2_poseidon::poseidon::{
+    primitives::{Domain, Mds, Spec},
+    PaddedWord, Pow5Chip, Sponge,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error, ProvingKey},
+};
+use rand::rngs::OsRng;
+use std::iter;
+use std::marker::PhantomData;
+
+/// Sponge domain for messages of arbitrary length.
+///
+/// As in section 4.2 of the Poseidon paper, the capacity starts at `2^64`
+/// (one output element) and the message is always extended with a `1`
+/// followed by the fewest zeros that fill the last block.
+#[derive(Clone, Copy, Debug)]
+pub struct VariableLength;
+
+impl<F: PrimeField, const RATE: usize> Domain<F, RATE> for VariableLength {
+    type Padding = Vec<F>;
+
+    fn name() -> String {
+        "VariableLength".to_string()
+    }
+
+    fn initial_capacity_element() -> F {
+        F::from_u128(1 << 64)
+    }
+
+    fn padding(input_len: usize) -> Self::Padding {
+        let zeros = RATE - 1 - input_len % RATE;
+        iter::once(F::ONE)
+            .chain(iter::repeat(F::ZERO).take(zeros))
+            .collect()
+    }
+}
+
+/// Hashes `message` natively with the [`VariableLength`] sponge.
+pub fn hash<F, S, const WIDTH: usize, const RATE: usize>(message: &[F]) -> F
+where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE>,
+{
+    let (round_constants, mds, _) = S::constants();
+    let mut state = [F::ZERO; WIDTH];
+    state[RATE] = <VariableLength as Domain<F, RATE>>::initial_capacity_element();
+
+    let padding = <VariableLength as Domain<F, RATE>>::padding(message.len());
+    let padded = message.iter().copied().chain(padding).collect::<Vec<_>>();
+    for block in padded.chunks(RATE) {
+        for (word, value) in state.iter_mut().zip(block) {
+            *word += value;
+        }
+        permute::<F, S, WIDTH, RATE>(&mut state, &mds, &round_constants);
+    }
+    state[0]
+}
+
+/// The Poseidon permutation, laid out as in the gadget's `Pow5Chip`.
+fn permute<F, S, const WIDTH: usize, const RATE: usize>(
+    state: &mut [F; WIDTH],
+    mds: &Mds<F, WIDTH>,
+    round_constants: &[[F; WIDTH]],
+) where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE>,
+{
+    let half_full = S::full_rounds() / 2;
+    let partial = half_full..half_full + S::partial_rounds();
+    for (round, constants) in round_constants.iter().enumerate() {
+        for (word, constant) in state.iter_mut().zip(constants) {
+            *word += constant;
+        }
+        if partial.contains(&round) {
+            state[0] = S::sbox(state[0]);
+        } else {
+            for word in state.iter_mut() {
+                *word = S::sbox(*word);
+            }
+        }
+        let old = *state;
+        for (word, row) in state.iter_mut().zip(mds) {
+            *word = row.iter().zip(&old).map(|(m, x)| *m * x).sum();
+        }
+    }
+}
+
+/// Circuit proving that the public input is the [`VariableLength`] hash of a
+/// `len`-element message.
+#[derive(Clone)]
+pub struct SpongeCircuit<F, S, const WIDTH: usize, const RATE: usize> {
+    message: Value<Vec<F>>,
+    len: usize,
+    _spec: PhantomData<S>,
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize> Circuit<F> for SpongeCircuit<F, S, WIDTH, RATE>
+where
+    F: PrimeField,
+    S: Spec<F, WIDTH, RATE> + Copy + Clone,
+{
+    type Config = MyConfig<F, WIDTH, RATE, RATE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: Value::unknown(),
+            len: self.len,
+            _spec: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // Same columns as the fixed-length circuit, with every rate column
+        // used to load the message.
+        HashCircuit::<F, S, WIDTH, RATE, RATE>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+        let message = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                (0..self.len)
+                    .map(|i| {
+                        let value = self.message.as_ref().map(|message| message[i]);
+                        region.assign_advice(
+                            || format!("load message_{}", i),
+                            config.input[i % RATE],
+                            i / RATE,
+                            || value,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let mut sponge = Sponge::<_, _, S, _, VariableLength, WIDTH, RATE>::new(
+            chip,
+            layouter.namespace(|| "init"),
+        )?;
+        for (i, word) in message.into_iter().enumerate() {
+            sponge.absorb(
+                layouter.namespace(|| format!("absorb message_{}", i)),
+                PaddedWord::Message(word),
+            )?;
+        }
+        let padding = <VariableLength as Domain<F, RATE>>::padding(self.len);
+        for (i, word) in padding.into_iter().enumerate() {
+            sponge.absorb(
+                layouter.namespace(|| format!("absorb padding_{}", i)),
+                PaddedWord::Padding(word),
+            )?;
+        }
+        let output = sponge
+            .finish_absorbing(layouter.namespace(|| "finish absorbing"))?
+            .squeeze(layouter.namespace(|| "squeeze"))?;
+
+        layouter.constrain_instance(output.cell(), config.expected, 0)
+    }
+}
+
+/// Variable-length Poseidon of a `len`-element message, proven with `B`.
+#[derive(Debug, Clone, Copy)]
+pub struct SpongeBenchmark<B, const WIDTH: usize, const RATE: usize> {
+    backend: B,
+    len: usize,
+}
+
+impl<B, const WIDTH: usize, const RATE: usize> SpongeBenchmark<B, WIDTH, RATE> {
+    /// Hashes messages of `len` field elements.
+    pub fn new(backend: B, len: usize) -> Self {
+        Self { backend, len }
+    }
+
+    fn empty_circuit(&self) -> <Self as HashBenchmark>::Circuit
+    where
+        B: Backend,
+    {
+        SpongeCircuit {
+            message: Value::unknown(),
+            len: self.len,
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<B: Backend, const WIDTH: usize, const RATE: usize> HashBenchmark
+    for SpongeBenchmark<B, WIDTH, RATE>
+{
+    type Params = B::Params;
+    type ProvingKey = ProvingKey<B::Curve>;
+    type Message = Vec<B::Field>;
+    type Digest = B::Field;
+    type Circuit = SpongeCircuit<B::Field, MySpec<WIDTH, RATE>, WIDTH, RATE>;
+
+    fn name(&self) -> String {
+        format!("poseidon-sponge-w{}-r{}-n{}", WIDTH, RATE, self.len)
+    }
+
+    fn message_len(&self) -> usize {
+        self.len
+    }
+
+    fn random_message(&self) -> Self::Message {
+        (0..self.len).map(|_| B::Field::random(OsRng)).collect()
+    }
+
+    fn native_hash(&self, message: &Self::Message) -> Self::Digest {
+        hash::<_, MySpec<WIDTH, RATE>, WIDTH, RATE>(message)
+    }
+
+    fn circuit(&self, message: Self::Message, _digest: &Self::Digest) -> Self::Circuit {
+        SpongeCircuit {
+            message: Value::known(message),
+            len: self.len,
+            _spec: PhantomData,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        let constants = <MySpec<WIDTH, RATE> as Spec<B::Field, WIDTH, RATE>>::constants();
+        circuit_fingerprint::<B::Field, Self::Circuit>(constants)
+    }
+
+    forward_to_backend!(backend, |digest| &[*digest]);
+}
+
+/// Runs [`SpongeBenchmark`] for `width` and `len`, or returns `None` if `width`
+/// is not one of [`WIDTHS`](crate::WIDTHS); the inner result fails as
+/// [`halo2_bench_harness::run`] does.
+pub fn run_width<B: Backend>(
+    backend: B,
+    width: usize,
+    len: usize,
+    config: &RunConfig,
+) -> Option<Result<BenchResult, String>> {
+    with_width!(width, W => halo2_bench_harness::run(
+        &SpongeBenchmark::<_, W, { W - 1 }>::new(backend, len),
+        config,
+    ))
+}
+
+/// Layout of the circuit [`run_width`] proves for `width` and `len` on `2^k`
+/// rows, or `None` if `width` is not one of [`WIDTHS`](crate::WIDTHS).
+pub fn circuit_stats_width<B: Backend>(
+    backend: B,
+    width: usize,
+    len: usize,
+    k: u32,
+) -> Option<CircuitStats> {
+    with_width!(width, W => SpongeBenchmark::<_, W, { W - 1 }>::new(backend, len).circuit_stats(k))
+}
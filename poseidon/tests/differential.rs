@@ -0,0 +1,134 @@
+//! The circuits must agree with the native hashes on any message, not only on
+//! the one a benchmark run happens to draw.
+//!
+//! Every case proves the native digest with `MockProver`, so a pinned gadget
+//! or primitive that drifts from the other fails here rather than in a
+//! benchmark.
+
+use ff::{Field, FromUniformBytes};
+use halo2_bench_backend::{Ipa, KzgShplonk};
+use halo2_bench_harness::merkle::MerklePath;
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2_bench_poseidon::batch::BatchBenchmark;
+use halo2_bench_poseidon::merkle::MerkleBenchmark;
+use halo2_bench_poseidon::sponge::SpongeBenchmark;
+use halo2_bench_poseidon::PoseidonBenchmark;
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::plonk::Circuit;
+use halo2curves::bn256::Fr;
+use halo2curves::pasta::Fp;
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const CASES: u32 = 256;
+
+/// Random field elements, with zero, one and minus one drawn far more often
+/// than chance would.
+fn element<F: Field>() -> impl Strategy<Value = F> {
+    prop_oneof![
+        1 => Just(F::ZERO),
+        1 => Just(F::ONE),
+        1 => Just(-F::ONE),
+        7 => any::<u64>().prop_map(|seed| F::random(StdRng::seed_from_u64(seed))),
+    ]
+}
+
+/// Paths of up to `max_depth` levels with arbitrary siblings.
+fn path<F: Field>(max_depth: usize) -> impl Strategy<Value = MerklePath<F>> {
+    let siblings = prop::collection::vec(element(), 1..=max_depth);
+    (element(), any::<u64>(), siblings).prop_map(|(leaf, index, siblings)| MerklePath {
+        leaf,
+        index: index & ((1 << siblings.len()) - 1),
+        siblings,
+    })
+}
+
+/// [`min_k`] of `bench`, computed once per benchmark rather than for every
+/// case.
+fn k<B: HashBenchmark>(bench: &B) -> u32 {
+    static K: OnceLock<Mutex<HashMap<(String, &'static str), u32>>> = OnceLock::new();
+    *K.get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry((bench.name(), bench.field()))
+        .or_insert_with(|| min_k(bench).expect("Poseidon circuits report their used rows"))
+}
+
+/// Checks with `MockProver` that `bench`'s circuit for `message` accepts the
+/// instance column `instance` makes out of the native digest.
+fn agrees<B, F>(
+    bench: &B,
+    message: B::Message,
+    instance: impl FnOnce(&B::Digest) -> Vec<F>,
+) -> Result<(), TestCaseError>
+where
+    B: HashBenchmark,
+    B::Circuit: Circuit<F>,
+    F: FromUniformBytes<64> + Ord,
+{
+    let digest = bench.native_hash(&message);
+    let instance = instance(&digest);
+    let circuit = bench.circuit(message, &digest);
+    let prover =
+        MockProver::run(k(bench), &circuit, vec![instance]).expect("synthesis should not fail");
+    prop_assert_eq!(prover.verify(), Ok(()));
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn width_3_matches_native(message in prop::array::uniform::<_, 2>(element::<Fp>())) {
+        agrees(&PoseidonBenchmark::<_, 3, 2, 2>(Ipa), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn width_4_matches_native(message in prop::array::uniform::<_, 3>(element::<Fp>())) {
+        agrees(&PoseidonBenchmark::<_, 4, 3, 3>(Ipa), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn width_5_matches_native(message in prop::array::uniform::<_, 4>(element::<Fp>())) {
+        agrees(&PoseidonBenchmark::<_, 5, 4, 4>(Ipa), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn width_9_matches_native(message in prop::array::uniform::<_, 8>(element::<Fp>())) {
+        agrees(&PoseidonBenchmark::<_, 9, 8, 8>(Ipa), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn width_12_matches_native(message in prop::array::uniform::<_, 11>(element::<Fp>())) {
+        agrees(&PoseidonBenchmark::<_, 12, 11, 11>(Ipa), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn bn254_matches_native(message in prop::array::uniform::<_, 2>(element::<Fr>())) {
+        agrees(&PoseidonBenchmark::<_, 3, 2, 2>(KzgShplonk), message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn batch_matches_native(
+        messages in prop::collection::vec(prop::array::uniform::<_, 2>(element::<Fp>()), 1..=4),
+    ) {
+        let bench = BatchBenchmark::<_, 3, 2, 2>::new(Ipa, messages.len());
+        agrees(&bench, messages, |digests| digests.clone())?;
+    }
+
+    /// Lengths on both sides of a multiple of the rate, where the padding
+    /// changes shape.
+    #[test]
+    fn sponge_matches_native(message in prop::collection::vec(element::<Fp>(), 1..=9)) {
+        let bench = SpongeBenchmark::<_, 3, 2>::new(Ipa, message.len());
+        agrees(&bench, message, |digest| vec![*digest])?;
+    }
+
+    #[test]
+    fn merkle_matches_native(path in path::<Fp>(8)) {
+        let bench = MerkleBenchmark::new(Ipa, path.depth());
+        agrees(&bench, path, |root| vec![*root])?;
+    }
+}
@@ -0,0 +1,109 @@
+//! The circuits must reject wrong digests and tampered proofs, not only accept
+//! the right ones.
+
+use ff::{Field, FromUniformBytes};
+use halo2_bench_backend::{Ipa, KzgShplonk};
+use halo2_bench_harness::{min_k, HashBenchmark};
+use halo2_bench_poseidon::batch::BatchBenchmark;
+use halo2_bench_poseidon::merkle::MerkleBenchmark;
+use halo2_bench_poseidon::sponge::SpongeBenchmark;
+use halo2_bench_poseidon::PoseidonBenchmark;
+use halo2_proofs::dev::{MockProver, VerifyFailure};
+use halo2_proofs::plonk::Circuit;
+
+/// Runs `MockProver` on `bench`'s circuit for a random message, with the
+/// instance column `instance` makes out of the correct digest.
+fn mock_prove<B, F>(
+    bench: &B,
+    instance: impl FnOnce(&B::Digest) -> Vec<F>,
+) -> Result<(), Vec<VerifyFailure>>
+where
+    B: HashBenchmark,
+    B::Circuit: Circuit<F>,
+    F: FromUniformBytes<64> + Ord,
+{
+    let k = min_k(bench).expect("Poseidon circuits report their used rows");
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    let instance = instance(&digest);
+    let circuit = bench.circuit(message, &digest);
+    MockProver::run(k, &circuit, vec![instance])
+        .expect("synthesis should not fail")
+        .verify()
+}
+
+/// Proves a random message with `bench`, then checks that the proof verifies
+/// against its digest but not against `wrong(digest)`, and that flipping a
+/// bit anywhere in it is caught.
+fn rejects_tampering<B: HashBenchmark>(bench: &B, wrong: impl Fn(&B::Digest) -> B::Digest) {
+    let k = min_k(bench).expect("Poseidon circuits report their used rows");
+    let params = bench.setup(k);
+    let pk = bench.keygen(&params);
+    let message = bench.random_message();
+    let digest = bench.native_hash(&message);
+    let proof = bench.prove(&params, &pk, bench.circuit(message, &digest), &digest);
+
+    assert!(bench.verify(&params, &pk, &proof, &digest));
+    assert!(!bench.verify(&params, &pk, &proof, &wrong(&digest)));
+
+    for byte in [0, proof.len() / 2, proof.len() - 1] {
+        let mut tampered = proof.clone();
+        tampered[byte] ^= 1;
+        assert!(
+            !bench.verify(&params, &pk, &tampered, &digest),
+            "flipped byte {} of {} still verifies",
+            byte,
+            proof.len()
+        );
+    }
+}
+
+fn plus_one<F: Field>(x: &F) -> F {
+    *x + F::ONE
+}
+
+#[test]
+fn hash_accepts_its_digest() {
+    let bench = PoseidonBenchmark::<_, 3, 2, 2>(Ipa);
+    assert_eq!(mock_prove(&bench, |digest| vec![*digest]), Ok(()));
+}
+
+#[test]
+fn hash_rejects_wrong_instance() {
+    let bench = PoseidonBenchmark::<_, 3, 2, 2>(Ipa);
+    assert!(mock_prove(&bench, |digest| vec![plus_one(digest)]).is_err());
+}
+
+#[test]
+fn batch_rejects_one_wrong_instance() {
+    let bench = BatchBenchmark::<_, 3, 2, 2>::new(Ipa, 4);
+    let result = mock_prove(&bench, |digests| {
+        let mut digests = digests.clone();
+        digests[3] = plus_one(&digests[3]);
+        digests
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn sponge_rejects_wrong_instance() {
+    let bench = SpongeBenchmark::<_, 3, 2>::new(Ipa, 5);
+    assert!(mock_prove(&bench, |digest| vec![plus_one(digest)]).is_err());
+}
+
+#[test]
+fn merkle_rejects_wrong_root() {
+    let bench = MerkleBenchmark::new(Ipa, 4);
+    assert_eq!(mock_prove(&bench, |root| vec![*root]), Ok(()));
+    assert!(mock_prove(&bench, |root| vec![plus_one(root)]).is_err());
+}
+
+#[test]
+fn ipa_rejects_tampered_proofs() {
+    rejects_tampering(&PoseidonBenchmark::<_, 3, 2, 2>(Ipa), plus_one);
+}
+
+#[test]
+fn kzg_rejects_tampered_proofs() {
+    rejects_tampering(&PoseidonBenchmark::<_, 3, 2, 2>(KzgShplonk), plus_one);
+}
@@ -0,0 +1,100 @@
+//! Round numbers and MDS selection must agree with the reference scripts of
+//! the Poseidon paper, and the polynomial arithmetic behind the MDS checks
+//! must tell reducible polynomials from irreducible ones.
+
+use ff::Field;
+use halo2_bench_poseidon::security::{is_irreducible, round_numbers, RoundNumbers};
+use halo2_bench_poseidon::{MySpec, WIDTHS};
+use halo2_poseidon::poseidon::primitives::{P128Pow5T3, Spec};
+use halo2curves::bn256::Fr;
+use halo2curves::pasta::Fp;
+
+/// `(width, R_F, R_P)` for each of [`WIDTHS`], as printed by
+/// `calc_round_numbers.py` for 128-bit security, `x^5` and the security
+/// margin. The Pasta and BN254 moduli give the same numbers.
+const REFERENCE: [(usize, usize, usize); 5] =
+    [(3, 8, 56), (4, 8, 56), (5, 8, 56), (9, 8, 57), (12, 8, 57)];
+
+/// Monic polynomial over Pasta with the given lower coefficients, lowest
+/// first.
+fn monic(coeffs: &[i64]) -> Vec<Fp> {
+    coeffs
+        .iter()
+        .map(|&c| {
+            let abs = Fp::from(c.unsigned_abs());
+            if c < 0 {
+                -abs
+            } else {
+                abs
+            }
+        })
+        .chain([Fp::ONE])
+        .collect()
+}
+
+#[test]
+fn round_numbers_match_reference() {
+    assert_eq!(REFERENCE.map(|(width, _, _)| width), WIDTHS);
+    for (width, full, partial) in REFERENCE {
+        let expected = RoundNumbers { full, partial };
+        assert_eq!(
+            round_numbers::<Fp>(width),
+            expected,
+            "Pasta, width {}",
+            width
+        );
+        assert_eq!(
+            round_numbers::<Fr>(width),
+            expected,
+            "BN254, width {}",
+            width
+        );
+    }
+}
+
+#[test]
+fn width_3_over_pasta_is_p128pow5t3() {
+    type Ours = MySpec<3, 2>;
+    assert_eq!(
+        <Ours as Spec<Fp, 3, 2>>::full_rounds(),
+        <P128Pow5T3 as Spec<Fp, 3, 2>>::full_rounds()
+    );
+    assert_eq!(
+        <Ours as Spec<Fp, 3, 2>>::partial_rounds(),
+        <P128Pow5T3 as Spec<Fp, 3, 2>>::partial_rounds()
+    );
+    assert_eq!(
+        <Ours as Spec<Fp, 3, 2>>::secure_mds(),
+        <P128Pow5T3 as Spec<Fp, 3, 2>>::secure_mds()
+    );
+    assert_eq!(
+        <Ours as Spec<Fp, 3, 2>>::constants(),
+        <P128Pow5T3 as Spec<Fp, 3, 2>>::constants()
+    );
+}
+
+/// 5 generates the multiplicative group of Pasta, so it is neither a square
+/// nor a cube, and `x^n - 5` is irreducible for `n` up to 4.
+#[test]
+fn detects_irreducible_polynomials() {
+    let cases: [&[i64]; 4] = [&[-3], &[-5, 0], &[-5, 0, 0], &[-5, 0, 0, 0]];
+    for coeffs in cases {
+        assert!(is_irreducible(&monic(coeffs)), "{:?}", coeffs);
+    }
+}
+
+#[test]
+fn detects_reducible_polynomials() {
+    let cases: [&[i64]; 4] = [
+        // -1 is a square, as p = 1 mod 4.
+        &[1, 0],
+        &[-4, 0],
+        // (x^2 - 5)(x^2 - 7): no root, but two irreducible quadratic factors.
+        &[35, 0, -12, 0],
+        // (x - 1)(x^3 - 5)
+        &[5, -5, 0, -1],
+    ];
+    for coeffs in cases {
+        assert!(!is_irreducible(&monic(coeffs)), "{:?}", coeffs);
+    }
+}
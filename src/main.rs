@@ -1,3 +1,111 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::process;
+
+mod output;
+mod suite;
+
+use output::{Format, Sink};
+use suite::{Bench, RunOptions};
+
+/// Benchmarks of hash circuits written with halo2.
+#[derive(Debug, Parser)]
+#[command(name = "halo2_bench")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// How to render each result
+    #[arg(long, value_enum, default_value_t = Format::Text, global = true)]
+    format: Format,
+
+    /// Append results to this file instead of printing them
+    #[arg(long, global = true)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List the available benchmarks
+    List,
+    /// Run a single benchmark
+    Run {
+        bench: Bench,
+        #[command(flatten)]
+        opts: RunOptions,
+    },
+    /// Run every benchmark in turn, except the insecure mimc-merkle
+    All {
+        #[command(flatten)]
+        opts: RunOptions,
+    },
+    /// Print the layout and smallest k of a benchmark's circuit without proving (ignores --k)
+    Stats {
+        bench: Bench,
+        #[command(flatten)]
+        opts: RunOptions,
+    },
+    /// Sweep Poseidon over state widths and message lengths (ignores --width)
+    Sweep {
+        #[command(flatten)]
+        opts: RunOptions,
+    },
+}
+
 fn main() {
-    println!("Hello, world!");
+    let cli = Cli::parse();
+
+    let mut sink = Sink::open(cli.format, cli.output.as_deref()).unwrap_or_else(|err| {
+        eprintln!("error: cannot open output: {}", err);
+        process::exit(1);
+    });
+
+    let benches = match cli.command {
+        Command::List => {
+            for bench in Bench::value_variants() {
+                let name = bench.to_possible_value().unwrap();
+                println!("{:<20} {}", name.get_name(), bench.description());
+            }
+            return;
+        }
+        Command::Run { bench, opts } => vec![(bench, opts)],
+        Command::All { opts } => Bench::value_variants()
+            .iter()
+            .filter(|bench| bench.in_all())
+            .map(|bench| (*bench, opts.clone()))
+            .collect(),
+        Command::Stats { bench, opts } => {
+            match suite::circuit_stats(bench, &opts) {
+                Ok((stats, k)) => println!("{:?}, min k {}", stats, k),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        Command::Sweep { opts } => {
+            let results = suite::sweep(&opts).unwrap_or_else(|err| {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            });
+            for result in results {
+                sink.write(&result)
+                    .expect("writing the result should not fail");
+            }
+            return;
+        }
+    };
+
+    for (bench, opts) in benches {
+        match suite::run(bench, &opts) {
+            Ok(result) => sink
+                .write(&result)
+                .expect("writing the result should not fail"),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
 }
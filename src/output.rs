@@ -0,0 +1,60 @@
+//! Where and how benchmark results are written.
+
+use clap::ValueEnum;
+use halo2_bench_harness::{report, BenchResult};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Rendering of each result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// `Debug` dump of the result
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+/// Destination for benchmark results.
+pub struct Sink {
+    format: Format,
+    out: Box<dyn Write>,
+    header_pending: bool,
+}
+
+impl Sink {
+    /// Writes to `path` in append mode, or to stdout when `path` is `None`.
+    ///
+    /// The CSV header is only emitted if the file is empty, so repeated runs
+    /// can accumulate in the same file.
+    pub fn open(format: Format, path: Option<&Path>) -> io::Result<Self> {
+        let (out, empty): (Box<dyn Write>, bool) = match path {
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                let empty = file.metadata()?.len() == 0;
+                (Box::new(file), empty)
+            }
+            None => (Box::new(io::stdout()), true),
+        };
+        Ok(Self {
+            format,
+            out,
+            header_pending: format == Format::Csv && empty,
+        })
+    }
+
+    pub fn write(&mut self, result: &BenchResult) -> io::Result<()> {
+        if self.header_pending {
+            writeln!(self.out, "{}", report::csv_header())?;
+            self.header_pending = false;
+        }
+        match self.format {
+            Format::Text => writeln!(self.out, "{:?}", result)?,
+            Format::Json => writeln!(self.out, "{}", report::json_line(result))?,
+            Format::Csv => writeln!(self.out, "{}", report::csv_row(result))?,
+        }
+        self.out.flush()
+    }
+}
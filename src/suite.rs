@@ -0,0 +1,344 @@
+//! Registry of the benchmarks shipped in this workspace.
+
+use clap::{Args, ValueEnum};
+use halo2_bench_anemoi::bn254;
+use halo2_bench_anemoi::merkle as anemoi_merkle;
+use halo2_bench_anemoi::pasta::AnemoiBenchmark;
+use halo2_bench_backend::{Backend, Ipa, KzgGwc, KzgShplonk};
+use halo2_bench_harness::{
+    max_hashes, BenchResult, CircuitStats, HashBenchmark, RunConfig, PROBE_K,
+};
+use halo2_bench_mimc::field::{MiMCField, Pallas, Vesta};
+use halo2_bench_mimc::{merkle as mimc_merkle, MiMC5Benchmark};
+use halo2_bench_poseidon::{batch, merkle, sponge, WIDTHS};
+use std::path::PathBuf;
+
+/// A benchmark selectable from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Bench {
+    Poseidon,
+    PoseidonSponge,
+    PoseidonMerkle,
+    Mimc,
+    MimcMerkle,
+    AnemoiBn254,
+    AnemoiPasta,
+    AnemoiSponge,
+    AnemoiJive,
+    AnemoiMerkle,
+}
+
+impl Bench {
+    /// One-line summary printed by `list`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Bench::Poseidon => "Poseidon (Pow5Chip), IPA on Pasta or KZG on BN254",
+            Bench::PoseidonSponge => "Variable-length Poseidon sponge, IPA or KZG",
+            Bench::PoseidonMerkle => "Merkle path with width-3 Poseidon, IPA or KZG",
+            Bench::Mimc => "MiMC5 over a Pasta base field, IPA on the other curve",
+            Bench::MimcMerkle => "MiMC5 Merkle path over a Pasta field, IPA; insecure, cost only",
+            Bench::AnemoiBn254 => "Own Anemoi gates over the BN254 scalar field, Delphinus KZG",
+            Bench::AnemoiPasta => "Anemoi over the Pallas base field, IPA on Vesta",
+            Bench::AnemoiSponge => "Long-message Anemoi sponge over the Pallas base field, IPA",
+            Bench::AnemoiJive => "Anemoi Jive compression over the Pallas base field, IPA",
+            Bench::AnemoiMerkle => "Merkle path with Anemoi Jive over the Pallas base field, IPA",
+        }
+    }
+
+    /// Whether `all` runs this benchmark. The MiMC Merkle tree is forgeable,
+    /// so it is only run when asked for by name.
+    pub fn in_all(self) -> bool {
+        self != Bench::MimcMerkle
+    }
+}
+
+/// Commitment scheme the Poseidon benchmarks are proven with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackendKind {
+    /// IPA on Vesta, circuit over the Pallas base field
+    Ipa,
+    /// KZG on BN254 with the GWC multiopen argument
+    KzgGwc,
+    /// KZG on BN254 with the SHPLONK multiopen argument
+    KzgShplonk,
+}
+
+/// Calls `$f` with the [`Backend`] selected by `$kind`, followed by `$arg`s.
+macro_rules! with_backend {
+    ($kind:expr, $f:path $(, $arg:expr)*) => {
+        match $kind {
+            BackendKind::Ipa => $f(Ipa, $($arg),*),
+            BackendKind::KzgGwc => $f(KzgGwc, $($arg),*),
+            BackendKind::KzgShplonk => $f(KzgShplonk, $($arg),*),
+        }
+    };
+}
+
+/// Field the MiMC benchmarks hash over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FieldKind {
+    /// Pallas base field, IPA on Vesta
+    Pallas,
+    /// Vesta base field, IPA on Pallas
+    Vesta,
+}
+
+/// Calls `$f` with the [`MiMCField`] selected by `$kind`, followed by `$arg`s.
+macro_rules! with_field {
+    ($kind:expr, $f:path $(, $arg:expr)*) => {
+        match $kind {
+            FieldKind::Pallas => $f(Pallas, $($arg),*),
+            FieldKind::Vesta => $f(Vesta, $($arg),*),
+        }
+    };
+}
+
+/// Options shared by `run`, `all` and `sweep`.
+#[derive(Debug, Clone, Args)]
+pub struct RunOptions {
+    /// log2 of the number of rows, defaults to the smallest that fits the circuit
+    #[arg(long)]
+    pub k: Option<u32>,
+
+    /// Poseidon state width, the message fills the whole rate
+    #[arg(long, default_value_t = 3)]
+    pub width: usize,
+
+    /// Message length in field elements for the Poseidon and Anemoi sponges
+    #[arg(long, default_value_t = 256)]
+    pub length: usize,
+
+    /// Commitment scheme for the Poseidon benchmarks
+    #[arg(long, value_enum, default_value_t = BackendKind::Ipa)]
+    pub backend: BackendKind,
+
+    /// Measured iterations per benchmark
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub iterations: u64,
+
+    /// Iterations run and discarded before measuring
+    #[arg(long, default_value_t = 0)]
+    pub warmup: u64,
+
+    /// Directory to keep params and proving keys in between runs
+    ///
+    /// Only KZG proving keys can be serialized. IPA benchmarks and
+    /// anemoi-bn254, on the Delphinus fork, cache their params alone, and still
+    /// run keygen every time.
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Independent hashes proven together in one circuit, except by the sponge
+    /// and Merkle paths
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub hashes: u64,
+
+    /// Batch as many hashes as fit in 2^k rows instead of --hashes (run and all)
+    #[arg(long, requires = "k", conflicts_with = "hashes")]
+    pub fill: bool,
+
+    /// Field for the MiMC benchmarks
+    #[arg(long, value_enum, default_value_t = FieldKind::Pallas)]
+    pub field: FieldKind,
+
+    /// Keep the MiMC digests in an advice column instead of making them public
+    #[arg(long)]
+    pub private_digest: bool,
+
+    /// Depth of the tree the Merkle path benchmarks prove membership in
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..64))]
+    pub depth: u64,
+}
+
+/// Runs `bench`.
+pub fn run(bench: Bench, opts: &RunOptions) -> Result<BenchResult, String> {
+    let config = config(opts);
+    match bench {
+        Bench::Poseidon => match hashes(opts) {
+            Some(1) => with_backend!(opts.backend, run_poseidon, opts.width, &config),
+            n => with_backend!(opts.backend, run_batch, opts.width, n, &config),
+        },
+        Bench::PoseidonSponge => with_backend!(opts.backend, run_sponge, opts, &config),
+        Bench::PoseidonMerkle => with_backend!(
+            opts.backend,
+            merkle::run_depth,
+            opts.depth as usize,
+            &config
+        ),
+        Bench::Mimc => with_field!(opts.field, run_mimc, opts, &config),
+        Bench::MimcMerkle => with_field!(
+            opts.field,
+            mimc_merkle::run_depth,
+            opts.depth as usize,
+            &config
+        ),
+        Bench::AnemoiBn254 => {
+            halo2_bench_harness::run(&batch_of(opts, bn254::AnemoiBenchmark::batch)?, &config)
+        }
+        Bench::AnemoiPasta => halo2_bench_harness::run(
+            &batch_of(opts, |n| AnemoiBenchmark::batch(Ipa, n))?,
+            &config,
+        ),
+        Bench::AnemoiSponge => {
+            halo2_bench_harness::run(&AnemoiBenchmark::sponge(Ipa, opts.length), &config)
+        }
+        Bench::AnemoiJive => {
+            halo2_bench_harness::run(&batch_of(opts, |n| AnemoiBenchmark::jive(Ipa, n))?, &config)
+        }
+        Bench::AnemoiMerkle => anemoi_merkle::run_depth(Ipa, opts.depth as usize, &config),
+    }
+}
+
+/// Layout of the circuit `run` would prove for `bench`, together with the
+/// smallest `k` that fits it.
+pub fn circuit_stats(bench: Bench, opts: &RunOptions) -> Result<(CircuitStats, u32), String> {
+    let stats = match bench {
+        Bench::Poseidon => match opts.hashes {
+            1 => with_backend!(
+                opts.backend,
+                halo2_bench_poseidon::circuit_stats_width,
+                opts.width,
+                PROBE_K
+            ),
+            n => with_backend!(
+                opts.backend,
+                batch::circuit_stats_width,
+                opts.width,
+                n as usize,
+                PROBE_K
+            ),
+        },
+        Bench::PoseidonSponge => with_backend!(
+            opts.backend,
+            sponge::circuit_stats_width,
+            opts.width,
+            opts.length,
+            PROBE_K
+        ),
+        Bench::PoseidonMerkle => Some(with_backend!(
+            opts.backend,
+            merkle::circuit_stats_depth,
+            opts.depth as usize,
+            PROBE_K
+        )),
+        Bench::Mimc => Some(with_field!(opts.field, mimc_stats, opts)),
+        Bench::MimcMerkle => Some(with_field!(
+            opts.field,
+            mimc_merkle::circuit_stats_depth,
+            opts.depth as usize,
+            PROBE_K
+        )),
+        Bench::AnemoiBn254 => {
+            Some(bn254::AnemoiBenchmark::batch(opts.hashes as usize).circuit_stats(PROBE_K))
+        }
+        Bench::AnemoiPasta => {
+            Some(AnemoiBenchmark::batch(Ipa, opts.hashes as usize).circuit_stats(PROBE_K))
+        }
+        Bench::AnemoiSponge => {
+            Some(AnemoiBenchmark::sponge(Ipa, opts.length).circuit_stats(PROBE_K))
+        }
+        Bench::AnemoiJive => {
+            Some(AnemoiBenchmark::jive(Ipa, opts.hashes as usize).circuit_stats(PROBE_K))
+        }
+        Bench::AnemoiMerkle => Some(anemoi_merkle::circuit_stats_depth(
+            Ipa,
+            opts.depth as usize,
+            PROBE_K,
+        )),
+    }
+    .ok_or_else(|| unsupported_width(opts.width))?;
+    let k = stats
+        .min_k()
+        .expect("proven benchmarks report their used rows");
+    Ok((stats, k))
+}
+
+fn run_poseidon<B: Backend>(
+    backend: B,
+    width: usize,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    halo2_bench_poseidon::run_width(backend, width, config)
+        .ok_or_else(|| unsupported_width(width))?
+}
+
+fn run_batch<B: Backend>(
+    backend: B,
+    width: usize,
+    n: Option<usize>,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    batch::run_width(backend, width, n, config).ok_or_else(|| unsupported_width(width))?
+}
+
+fn run_sponge<B: Backend>(
+    backend: B,
+    opts: &RunOptions,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    sponge::run_width(backend, opts.width, opts.length, config)
+        .ok_or_else(|| unsupported_width(opts.width))?
+}
+
+fn run_mimc<M: MiMCField>(
+    field: M,
+    opts: &RunOptions,
+    config: &RunConfig,
+) -> Result<BenchResult, String> {
+    if opts.private_digest {
+        halo2_bench_harness::run(
+            &batch_of(opts, |n| MiMC5Benchmark::private(field, n))?,
+            config,
+        )
+    } else {
+        halo2_bench_harness::run(
+            &batch_of(opts, |n| MiMC5Benchmark::batch(field, n))?,
+            config,
+        )
+    }
+}
+
+fn mimc_stats<M: MiMCField>(field: M, opts: &RunOptions) -> CircuitStats {
+    let n = opts.hashes as usize;
+    if opts.private_digest {
+        MiMC5Benchmark::private(field, n).circuit_stats(PROBE_K)
+    } else {
+        MiMC5Benchmark::batch(field, n).circuit_stats(PROBE_K)
+    }
+}
+
+fn unsupported_width(width: usize) -> String {
+    format!(
+        "unsupported Poseidon width {}, expected one of {:?}",
+        width, WIDTHS
+    )
+}
+
+/// Hashes per proof, or `None` if `--fill` asks for as many as fit.
+fn hashes(opts: &RunOptions) -> Option<usize> {
+    (!opts.fill).then_some(opts.hashes as usize)
+}
+
+/// `bench` with the hashes per proof asked for in `opts`.
+fn batch_of<B: HashBenchmark>(opts: &RunOptions, bench: impl Fn(usize) -> B) -> Result<B, String> {
+    let n = match hashes(opts) {
+        Some(n) => n,
+        None => max_hashes(opts.k.ok_or("--fill needs --k")?, &bench)?,
+    };
+    Ok(bench(n))
+}
+
+/// Runs the Poseidon width and message-length sweep.
+pub fn sweep(opts: &RunOptions) -> Result<Vec<BenchResult>, String> {
+    let config = config(opts);
+    with_backend!(opts.backend, halo2_bench_poseidon::sweep, &config)
+}
+
+fn config(opts: &RunOptions) -> RunConfig {
+    RunConfig {
+        k: opts.k,
+        iterations: opts.iterations as usize,
+        warmup: opts.warmup as usize,
+        cache: opts.cache_dir.clone(),
+    }
+}